@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{transfer_hook::TransferHookAccount, BaseStateWithExtensions, StateWithExtensions},
+    state::Account as Token2022Account,
+};
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed};
+
+use diamond::{constants::*, error::*};
+
+// Indexes into the Execute account list fixed by the interface
+const SOURCE_INDEX: u8 = 0;
+const DESTINATION_INDEX: u8 = 2;
+const AUTHORITY_INDEX: u8 = 3;
+// The DIAMOND program, the first extra account, owns every other one
+const DIAMOND_PROGRAM_INDEX: u8 = 5;
+
+// Token account layout: owner at 32, delegate pubkey after the 4-byte
+// COption tag at 72 (zeroed when there is no delegate)
+const OWNER_OFFSET: u8 = 32;
+const DELEGATE_OFFSET: u8 = 76;
+
+/// Accounts Token-2022 appends to Execute, in `TransferHook` order after the
+/// `ExtraAccountMetaList` PDA. All but the first are DIAMOND program PDAs.
+pub fn extra_account_metas() -> Result<Vec<ExtraAccountMeta>> {
+    let blacklist_seed = || Seed::Literal {
+        bytes: BLACKLIST_SEED.to_vec(),
+    };
+    let account_data = |account_index, data_index| Seed::AccountData {
+        account_index,
+        data_index,
+        length: 32,
+    };
+
+    let diamond_pda = |seeds: &[Seed]| {
+        ExtraAccountMeta::new_external_pda_with_seeds(DIAMOND_PROGRAM_INDEX, seeds, false, false)
+    };
+
+    Ok(vec![
+        // diamond_program
+        ExtraAccountMeta::new_with_pubkey(&diamond::ID, false, false)?,
+        // source_owner_blacklist_entry
+        diamond_pda(&[blacklist_seed(), account_data(SOURCE_INDEX, OWNER_OFFSET)])?,
+        // destination_owner_blacklist_entry
+        diamond_pda(&[
+            blacklist_seed(),
+            account_data(DESTINATION_INDEX, OWNER_OFFSET),
+        ])?,
+        // authority_blacklist_entry
+        diamond_pda(&[
+            blacklist_seed(),
+            Seed::AccountKey {
+                index: AUTHORITY_INDEX,
+            },
+        ])?,
+        // source_delegate_blacklist_entry
+        diamond_pda(&[
+            blacklist_seed(),
+            account_data(SOURCE_INDEX, DELEGATE_OFFSET),
+        ])?,
+        // destination_owner_kyc_record
+        diamond_pda(&[
+            Seed::Literal {
+                bytes: KYC_SEED.to_vec(),
+            },
+            account_data(DESTINATION_INDEX, OWNER_OFFSET),
+        ])?,
+        // config
+        diamond_pda(&[Seed::Literal {
+            bytes: CONFIG_SEED.to_vec(),
+        }])?,
+        // token_state
+        diamond_pda(&[Seed::Literal {
+            bytes: TOKEN_STATE_SEED.to_vec(),
+        }])?,
+    ])
+}
+
+/// Token-2022 sets the `transferring` flag on both token accounts for the
+/// duration of the hook CPI, so a direct call cannot pass this check.
+pub fn require_transferring(token_account: &AccountInfo) -> Result<()> {
+    let data = token_account.try_borrow_data()?;
+    let account = StateWithExtensions::<Token2022Account>::unpack(&data)?;
+    let extension = account
+        .get_extension::<TransferHookAccount>()
+        .map_err(|_| DiamondTokenError::NotTransferring)?;
+
+    require!(
+        bool::from(extension.transferring),
+        DiamondTokenError::NotTransferring
+    );
+
+    Ok(())
+}
@@ -0,0 +1,208 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use diamond::{blacklist, compliance, constants::*, error::*, program::Diamond, state::*};
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+use spl_transfer_hook_interface::instruction::{ExecuteInstruction, TransferHookInstruction};
+
+declare_id!("2X6obQLPTHEXfigGVnftF9LPmrS4cE3RvZ9wo3nrVDTi");
+
+pub mod hook;
+
+// Fixed by the SPL transfer-hook interface
+pub const EXTRA_ACCOUNT_METAS_SEED: &[u8] = b"extra-account-metas";
+
+/// Transfer hook for the DIAMOND mint. It lives apart from the DIAMOND program
+/// so that transfers the DIAMOND program issues itself can reach it: the
+/// runtime forbids reentering a program already on the CPI stack.
+#[program]
+pub mod diamond_transfer_hook {
+    use super::*;
+
+    pub fn initialize_extra_account_meta_list(
+        ctx: Context<InitializeExtraAccountMetaList>,
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        let extra_account_metas = hook::extra_account_metas()?;
+        ExtraAccountMetaList::init::<ExecuteInstruction>(
+            &mut ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?,
+            &extra_account_metas,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn on_transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+        // Only Token-2022 may call this, in the middle of a transfer.
+        // Confidential transfers arrive here too, with amount u64::MAX
+        hook::require_transferring(&ctx.accounts.source.to_account_info())?;
+        hook::require_transferring(&ctx.accounts.destination.to_account_info())?;
+
+        let current_time = Clock::get()?.unix_timestamp;
+
+        // Seizures move funds from blacklisted holders: the token state PDA
+        // signs as permanent delegate and the funds go to the seizure escrow
+        let (seizure_escrow, _) =
+            Pubkey::find_program_address(&[SEIZURE_ESCROW_SEED], &diamond::ID);
+        let is_seizure = ctx.accounts.authority.key() == ctx.accounts.token_state.key()
+            && ctx.accounts.destination.key() == seizure_escrow;
+
+        // Check the wallets behind the token accounts, not the token accounts
+        // themselves, so a blacklisted wallet cannot escape by opening a new one
+        if !is_seizure {
+            compliance::require_not_blacklisted(
+                &ctx.accounts.source_owner_blacklist_entry,
+                current_time,
+                DiamondTokenError::SourceAddressBlacklisted,
+            )?;
+        }
+        compliance::require_not_blacklisted(
+            &ctx.accounts.destination_owner_blacklist_entry,
+            current_time,
+            DiamondTokenError::DestinationAddressBlacklisted,
+        )?;
+        // Program-owned destinations and listed ones such as AMM pools have no KYC
+        if !compliance::is_kyc_exempt(
+            &ctx.accounts.config,
+            &ctx.accounts.destination.owner,
+            &ctx.accounts.token_state.key(),
+        ) {
+            compliance::require_kyc_verified(
+                &ctx.accounts.config,
+                &ctx.accounts.destination_owner_kyc_record,
+                current_time,
+            )?;
+        }
+
+        // Whoever signs the transfer, owner or delegate
+        compliance::require_not_blacklisted(
+            &ctx.accounts.authority_blacklist_entry,
+            current_time,
+            DiamondTokenError::DelegateBlacklisted,
+        )?;
+
+        // A blacklisted delegate keeps its allowance, so refuse to move delegated funds at all.
+        // The entry is derived from the zero key when there is no delegate.
+        let delegate = ctx.accounts.source.delegate;
+        blacklist::verify_entry_address(
+            &ctx.accounts.source_delegate_blacklist_entry,
+            &delegate.unwrap_or_default(),
+        )?;
+        if delegate.is_some() {
+            compliance::require_not_blacklisted(
+                &ctx.accounts.source_delegate_blacklist_entry,
+                current_time,
+                DiamondTokenError::DelegateBlacklisted,
+            )?;
+        }
+
+        // Emit event for successful transfer
+        emit!(TransferHookExecuted {
+            source: ctx.accounts.source.key(),
+            destination: ctx.accounts.destination.key(),
+            source_owner: ctx.accounts.source.owner,
+            destination_owner: ctx.accounts.destination.owner,
+            authority: ctx.accounts.authority.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Token-2022 calls the hook with the SPL interface `Execute`
+    /// discriminator instead of Anchor's, so route it here.
+    pub fn fallback<'info>(
+        program_id: &Pubkey,
+        accounts: &'info [AccountInfo<'info>],
+        data: &[u8],
+    ) -> Result<()> {
+        match TransferHookInstruction::unpack(data)? {
+            TransferHookInstruction::Execute { amount } => {
+                __private::__global::on_transfer_hook(program_id, accounts, &amount.to_le_bytes())
+            }
+            _ => Err(ProgramError::InvalidInstructionData.into()),
+        }
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump, seeds::program = diamond::ID)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: ExtraAccountMetaList PDA, written in the instruction
+    #[account(
+        init,
+        payer = authority,
+        space = ExtraAccountMetaList::size_of(hook::extra_account_metas()?.len())?,
+        seeds = [EXTRA_ACCOUNT_METAS_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Account order follows the SPL transfer-hook `Execute` interface; the
+/// accounts after `extra_account_meta_list` come from
+/// `hook::extra_account_metas`.
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    #[account(token::mint = mint)]
+    pub source: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(token::mint = mint)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Transfer authority, the source owner or its delegate
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: ExtraAccountMetaList PDA, read by Token-2022
+    #[account(seeds = [EXTRA_ACCOUNT_METAS_SEED, mint.key().as_ref()], bump)]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+    pub diamond_program: Program<'info, Diamond>,
+    /// CHECK: Blacklist entry PDA for the source owner, may not exist
+    #[account(seeds = [BLACKLIST_SEED, source.owner.as_ref()], bump, seeds::program = diamond::ID)]
+    pub source_owner_blacklist_entry: UncheckedAccount<'info>,
+    /// CHECK: Blacklist entry PDA for the destination owner, may not exist
+    #[account(
+        seeds = [BLACKLIST_SEED, destination.owner.as_ref()],
+        bump,
+        seeds::program = diamond::ID
+    )]
+    pub destination_owner_blacklist_entry: UncheckedAccount<'info>,
+    /// CHECK: Blacklist entry PDA for the transfer authority, may not exist
+    #[account(
+        seeds = [BLACKLIST_SEED, authority.key().as_ref()],
+        bump,
+        seeds::program = diamond::ID
+    )]
+    pub authority_blacklist_entry: UncheckedAccount<'info>,
+    /// CHECK: Blacklist entry PDA for the source delegate, validated in the instruction
+    pub source_delegate_blacklist_entry: UncheckedAccount<'info>,
+    /// CHECK: KYC record PDA for the destination owner, may not exist
+    #[account(seeds = [KYC_SEED, destination.owner.as_ref()], bump, seeds::program = diamond::ID)]
+    pub destination_owner_kyc_record: UncheckedAccount<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump, seeds::program = diamond::ID)]
+    pub config: Account<'info, Config>,
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump, seeds::program = diamond::ID)]
+    pub token_state: Account<'info, TokenState>,
+}
+
+#[event]
+pub struct TransferHookExecuted {
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub source_owner: Pubkey,
+    pub destination_owner: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+}
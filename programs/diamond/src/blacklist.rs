@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::{compliance, constants::*, error::*, state::*};
+
+/// An address is blacklisted while its `BlacklistEntry` PDA exists and has not
+/// expired. Callers must have checked that `entry` is the PDA for the address.
+pub fn is_blacklisted(entry: &AccountInfo, now: i64) -> Result<bool> {
+    if !entry_exists(entry) {
+        return Ok(false);
+    }
+
+    let data = entry.try_borrow_data()?;
+    let record = BlacklistEntry::try_deserialize(&mut &data[..])?;
+
+    Ok(record.is_active(now))
+}
+
+pub fn entry_exists(entry: &AccountInfo) -> bool {
+    entry.owner == &crate::ID && !entry.data_is_empty()
+}
+
+/// Checks that `entry` is the `BlacklistEntry` PDA for `address` and returns its bump.
+/// Needed wherever entries arrive through `remaining_accounts`.
+pub fn verify_entry_address(entry: &AccountInfo, address: &Pubkey) -> Result<u8> {
+    let (expected, bump) =
+        Pubkey::find_program_address(&[BLACKLIST_SEED, address.as_ref()], &crate::ID);
+    require_keys_eq!(entry.key(), expected, DiamondTokenError::InvalidBlacklist);
+
+    Ok(bump)
+}
+
+/// Creates the `BlacklistEntry` PDA at `entry`, paid by `payer`. `record.bump`
+/// must come from `verify_entry_address`.
+pub fn create_entry<'info>(
+    entry: &AccountInfo<'info>,
+    record: BlacklistEntry,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let address = record.address;
+    compliance::create_pda(
+        entry,
+        BlacklistEntry::LEN,
+        &[BLACKLIST_SEED, address.as_ref(), &[record.bump]],
+        payer,
+        system_program,
+    )?;
+
+    write_entry(entry, &record)
+}
+
+/// Overwrites an existing entry, used to refresh one that has expired.
+pub fn write_entry(entry: &AccountInfo, record: &BlacklistEntry) -> Result<()> {
+    let mut data = entry.try_borrow_mut_data()?;
+    record.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
+
+/// Closes an existing entry the way Anchor's `close` constraint does, refunding
+/// rent to `destination`.
+pub fn close_entry<'info>(
+    entry: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+) -> Result<()> {
+    let lamports = entry.lamports();
+    **destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(DiamondTokenError::MathOverflow)?;
+    **entry.try_borrow_mut_lamports()? = 0;
+
+    entry.assign(&system_program::ID);
+    entry.realloc(0, false)?;
+
+    Ok(())
+}
@@ -0,0 +1,345 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_2022::spl_token_2022::{
+    self, extension::permanent_delegate::PermanentDelegate,
+};
+use anchor_spl::token_interface::{self, FreezeAccount, ThawAccount, TokenAccount};
+
+use crate::{blacklist, constants::*, error::*, events::*, kyc, state::*};
+
+/// Fails with `error` when the wallet behind `entry` is actively blacklisted.
+/// `entry` must already be checked to be the wallet's `BlacklistEntry` PDA,
+/// either by a seeds constraint or `blacklist::verify_entry_address`.
+pub fn require_not_blacklisted(
+    entry: &AccountInfo,
+    now: i64,
+    error: DiamondTokenError,
+) -> Result<()> {
+    if blacklist::is_blacklisted(entry, now)? {
+        return Err(error.into());
+    }
+
+    Ok(())
+}
+
+/// In allow-list mode, fails unless the wallet behind `record` holds a valid
+/// `KycRecord`. `record` must already be checked to be the wallet's PDA.
+pub fn require_kyc_verified(config: &Config, record: &AccountInfo, now: i64) -> Result<()> {
+    if config.compliance_mode == ComplianceMode::DenyList {
+        return Ok(());
+    }
+
+    require!(
+        kyc::record_tier(record, now)? > KycTier::Unverified,
+        DiamondTokenError::KycRequired
+    );
+
+    Ok(())
+}
+
+/// True when DIAMOND sent to accounts owned by `owner` needs no KYC record:
+/// this program's token state and fee treasury PDAs (vault, escrow, reserve,
+/// buyback and fee accounts) and owners governance lists, such as AMM pools.
+pub fn is_kyc_exempt(config: &Config, owner: &Pubkey, token_state: &Pubkey) -> bool {
+    if owner == token_state || config.kyc_exempt_owners.contains(owner) {
+        return true;
+    }
+
+    let (fee_treasury, _) = Pubkey::find_program_address(&[FEE_TREASURY_SEED], &crate::ID);
+    *owner == fee_treasury
+}
+
+/// Auto-freeze for a blacklisting. `accounts` are (DIAMOND token account,
+/// `FreezeRecord` PDA) pairs, each token account owned by one of `wallets`.
+///
+/// Only the accounts passed in are frozen; nothing here can find a wallet's
+/// other accounts, so callers must pass all of them. On Token-2022 mints the
+/// transfer hook blocks the wallet regardless.
+///
+/// Accounts frozen here are marked in their record, so that removing the
+/// blacklisting thaws them and nothing else. Accounts already held by another
+/// recorded reason are marked too, so that lifting that reason cannot thaw
+/// them. Accounts frozen without a record, such as by the default account
+/// state, are left unmarked.
+#[allow(clippy::too_many_arguments)]
+pub fn freeze_blacklisted_accounts<'info>(
+    accounts: &'info [AccountInfo<'info>],
+    wallets: &[Pubkey],
+    mint: &AccountInfo<'info>,
+    freeze_authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let pairs = accounts.chunks_exact(2);
+    require!(
+        pairs.remainder().is_empty(),
+        DiamondTokenError::InvalidBatchSize
+    );
+    let timestamp = Clock::get()?.unix_timestamp;
+
+    for pair in pairs {
+        let (account_info, record_info) = (&pair[0], &pair[1]);
+        let account = load_wallet_account(account_info, wallets, mint)?;
+        let bump = verify_freeze_record_address(record_info, account_info.key)?;
+
+        let frozen = account.is_frozen();
+        let mut record = match load_freeze_record(record_info)? {
+            Some(record) => record,
+            None if frozen => continue,
+            None => {
+                create_pda(
+                    record_info,
+                    FreezeRecord::LEN,
+                    &[FREEZE_RECORD_SEED, account_info.key.as_ref(), &[bump]],
+                    payer,
+                    system_program,
+                )?;
+                FreezeRecord {
+                    token_account: account_info.key(),
+                    governance: false,
+                    blacklist: false,
+                    seized_amount: 0,
+                    bump,
+                }
+            }
+        };
+        if !record.hold_for_blacklist(frozen) {
+            continue;
+        }
+        write_freeze_record(record_info, &record)?;
+
+        if frozen {
+            continue;
+        }
+
+        set_frozen(
+            account_info,
+            true,
+            mint,
+            freeze_authority,
+            token_program,
+            signer,
+        )?;
+
+        emit!(AccountFrozen {
+            account: account_info.key(),
+            owner: account.owner,
+            authority: freeze_authority.key(),
+            timestamp,
+        });
+    }
+
+    Ok(())
+}
+
+/// Undoes `freeze_blacklisted_accounts` for the same (token account,
+/// `FreezeRecord` PDA) pairs: clears the blacklist mark and thaws the account
+/// unless another reason still holds it. Unmarked accounts are skipped.
+pub fn thaw_blacklisted_accounts<'info>(
+    accounts: &'info [AccountInfo<'info>],
+    wallets: &[Pubkey],
+    mint: &AccountInfo<'info>,
+    freeze_authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let pairs = accounts.chunks_exact(2);
+    require!(
+        pairs.remainder().is_empty(),
+        DiamondTokenError::InvalidBatchSize
+    );
+    let timestamp = Clock::get()?.unix_timestamp;
+
+    for pair in pairs {
+        let (account_info, record_info) = (&pair[0], &pair[1]);
+        let account = load_wallet_account(account_info, wallets, mint)?;
+        verify_freeze_record_address(record_info, account_info.key)?;
+
+        let mut record = match load_freeze_record(record_info)? {
+            Some(record) if record.blacklist => record,
+            _ => continue,
+        };
+        record.blacklist = false;
+        write_freeze_record(record_info, &record)?;
+
+        if record.is_held() || !account.is_frozen() {
+            continue;
+        }
+
+        set_frozen(
+            account_info,
+            false,
+            mint,
+            freeze_authority,
+            token_program,
+            signer,
+        )?;
+
+        emit!(AccountThawed {
+            account: account_info.key(),
+            owner: account.owner,
+            authority: freeze_authority.key(),
+            timestamp,
+        });
+    }
+
+    Ok(())
+}
+
+/// A DIAMOND token account owned by one of `wallets`.
+fn load_wallet_account<'info>(
+    account_info: &'info AccountInfo<'info>,
+    wallets: &[Pubkey],
+    mint: &AccountInfo<'info>,
+) -> Result<InterfaceAccount<'info, TokenAccount>> {
+    let account = InterfaceAccount::<TokenAccount>::try_from(account_info)?;
+    require!(
+        account.mint == mint.key() && wallets.contains(&account.owner),
+        DiamondTokenError::InvalidTokenAccount
+    );
+
+    Ok(account)
+}
+
+/// Checks that `record` is the `FreezeRecord` PDA for `token_account` and returns its bump.
+pub fn verify_freeze_record_address(record: &AccountInfo, token_account: &Pubkey) -> Result<u8> {
+    let (expected, bump) =
+        Pubkey::find_program_address(&[FREEZE_RECORD_SEED, token_account.as_ref()], &crate::ID);
+    require_keys_eq!(
+        record.key(),
+        expected,
+        DiamondTokenError::InvalidTokenAccount
+    );
+
+    Ok(bump)
+}
+
+/// The `FreezeRecord` at `record`, if one was ever written. `record` must
+/// already be checked to be the token account's PDA.
+pub fn load_freeze_record(record: &AccountInfo) -> Result<Option<FreezeRecord>> {
+    if record.owner != &crate::ID || record.data_is_empty() {
+        return Ok(None);
+    }
+
+    let data = record.try_borrow_data()?;
+    Ok(Some(FreezeRecord::try_deserialize(&mut &data[..])?))
+}
+
+fn write_freeze_record(record: &AccountInfo, value: &FreezeRecord) -> Result<()> {
+    let mut data = record.try_borrow_mut_data()?;
+    value.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
+
+pub fn set_frozen<'info>(
+    token_account: &AccountInfo<'info>,
+    frozen: bool,
+    mint: &AccountInfo<'info>,
+    freeze_authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    if frozen {
+        token_interface::freeze_account(CpiContext::new_with_signer(
+            token_program.clone(),
+            FreezeAccount {
+                account: token_account.clone(),
+                mint: mint.clone(),
+                authority: freeze_authority.clone(),
+            },
+            signer,
+        ))
+    } else {
+        token_interface::thaw_account(CpiContext::new_with_signer(
+            token_program.clone(),
+            ThawAccount {
+                account: token_account.clone(),
+                mint: mint.clone(),
+                authority: freeze_authority.clone(),
+            },
+            signer,
+        ))
+    }
+}
+
+/// True when `mint` is a Token-2022 mint whose permanent delegate is `delegate`.
+pub fn has_permanent_delegate(mint: &AccountInfo, delegate: &Pubkey) -> bool {
+    if mint.owner != &spl_token_2022::ID {
+        return false;
+    }
+
+    token_interface::get_mint_extension_data::<PermanentDelegate>(mint)
+        .map(|extension| Option::<Pubkey>::from(extension.delegate) == Some(*delegate))
+        .unwrap_or(false)
+}
+
+/// Creates a PDA owned by this program at `account`, paid by `payer`, the way
+/// Anchor's `init` does. Needed wherever PDAs arrive through `remaining_accounts`.
+pub fn create_pda<'info>(
+    account: &AccountInfo<'info>,
+    space: usize,
+    seeds: &[&[u8]],
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let signer = &[seeds];
+
+    let required_lamports = Rent::get()?.minimum_balance(space);
+    let current_lamports = account.lamports();
+
+    if current_lamports == 0 {
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                system_program::CreateAccount {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+                signer,
+            ),
+            required_lamports,
+            space as u64,
+            &crate::ID,
+        )?;
+    } else {
+        // Someone pre-funded the address, so create_account would fail
+        let top_up = required_lamports.saturating_sub(current_lamports);
+        if top_up > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    system_program::Transfer {
+                        from: payer.clone(),
+                        to: account.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                system_program::Allocate {
+                    account_to_allocate: account.clone(),
+                },
+                signer,
+            ),
+            space as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                system_program::Assign {
+                    account_to_assign: account.clone(),
+                },
+                signer,
+            ),
+            &crate::ID,
+        )?;
+    }
+
+    Ok(())
+}
@@ -14,25 +14,71 @@ pub const MAX_SUPPLY: u64 = 100_000_000 * 10u64.pow(DECIMALS as u32); // 100 mil
 pub const TOKEN_PRICE_USDT: u64 = 1_000_000; // 1 USDT
 pub const TOKEN_PRICE_USDC: u64 = 800_000; // 0.8 USDC
 pub const TOKEN_PRICE_SOL: u64 = 0; // To be set based on oracle price
+pub const TOKEN_PRICE_USD: u64 = 800_000; // 0.8 USD, for oracle-priced payments
 pub const MIN_PURCHASE_AMOUNT: u64 = 1_000_000; // 1 USDT
 pub const MAX_PURCHASE_AMOUNT: u64 = 1_000_000_000; // 1000 USDT
 pub const MIN_PURCHASE_USDC: u64 = 1_000_000; // 1 USDC
 pub const MIN_PURCHASE_SOL: u64 = 1_000_000; // 0.001 SOL
 
+// Fees
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const MAX_BUY_FEE_BPS: u16 = 300; // 3%
+pub const MAX_REDEEM_FEE_BPS: u16 = 300; // 3%
+pub const MAX_PURCHASE_FEE_BPS: u16 = 300; // 3%
+pub const MAX_TRANSFER_FEE_BPS: u16 = 100; // 1%
+pub const MAX_INTEREST_RATE_BPS: i16 = 1_000; // 10% a year
+pub const INTEREST_RATE_TIMELOCK: i64 = 2 * 86_400; // 2 days
+
+// Volume discounts
+pub const MAX_VOLUME_TIERS: usize = 8;
+pub const MAX_VOLUME_DISCOUNT_BPS: u16 = 2_000; // 20%
+
 // Price Oracle
 pub const PYTH_SOL_USD_PRICE_FEED: &str = "H6ARHf6YXhGYeQfUzQNGk6rDNnLBQKrenN712K4AQJEG";
+pub const SOL_USD_PRICE_FEED_PUBKEY: Pubkey = Pubkey::from_str_const(PYTH_SOL_USD_PRICE_FEED);
 pub const PRICE_CONFIDENCE_THRESHOLD: u64 = 100; // 1%
 pub const MAX_PRICE_AGE: i64 = 60; // 60 seconds
 
 // Time constants
 pub const PAUSE_COOLDOWN: i64 = 900; // 15 minutes in seconds
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+// Batch limits
+pub const MAX_BATCH_BURN_ACCOUNTS: usize = 16;
+pub const MAX_BATCH_BLACKLIST_ACCOUNTS: usize = 20;
+pub const MAX_HARVEST_ACCOUNTS: usize = 20;
+pub const MAX_RESERVE_VAULTS: usize = 8;
+
+// Reserve history
+pub const RESERVE_HISTORY_LEN: usize = 16;
+pub const MIN_RESERVE_ATTESTATION_INTERVAL: i64 = 3_600; // 1 hour
+
+// Max supply schedule
+pub const MAX_SCHEDULED_REDUCTIONS: usize = 32;
+
+// KYC and compliance
+pub const KYC_TIER_COUNT: usize = 3;
+pub const MAX_DENIED_JURISDICTIONS: usize = 32;
+pub const MAX_KYC_EXEMPT_OWNERS: usize = 8;
 
 // PDA seeds
 pub const TOKEN_STATE_SEED: &[u8] = b"token_state_v2";
 pub const BLACKLIST_SEED: &[u8] = b"blacklist_v2";
 pub const VAULT_SEED: &[u8] = b"vault_v2";
 pub const MULTISIG_SEED: &[u8] = b"multisig";
+pub const MAX_SUPPLY_SCHEDULE_SEED: &[u8] = b"max_supply_schedule";
+pub const CONFIG_SEED: &[u8] = b"config";
+pub const FEE_TREASURY_SEED: &[u8] = b"fee_treasury";
+pub const SEIZURE_ESCROW_SEED: &[u8] = b"seizure_escrow";
+pub const SEIZURE_SEED: &[u8] = b"seizure";
+pub const KYC_SEED: &[u8] = b"kyc";
+pub const USER_VOLUME_SEED: &[u8] = b"user_volume";
+pub const RESERVE_HISTORY_SEED: &[u8] = b"reserve_history";
+pub const BUYBACK_AUTHORITY_SEED: &[u8] = b"buyback_authority";
+pub const FREEZE_RECORD_SEED: &[u8] = b"freeze_record";
 
 // Token Mints
 pub const USDT_MINT: &str = "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB";
 pub const USDT_PUBKEY: Pubkey = Pubkey::from_str_const(USDT_MINT);
+pub const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+pub const USDC_PUBKEY: Pubkey = Pubkey::from_str_const(USDC_MINT);
@@ -32,6 +32,7 @@ pub enum DiamondTokenError {
     #[msg("Insufficient balance")]
     InsufficientBalance,
 
+    // Unused; kept so that later error codes keep their values
     #[msg("Insufficient reserve")]
     InsufficientReserve,
 
@@ -88,4 +89,130 @@ pub enum DiamondTokenError {
 
     #[msg("Arithmetic overflow")]
     ArithmeticOverflow,
+
+    #[msg("Invalid max supply schedule")]
+    InvalidMaxSupplySchedule,
+
+    #[msg("Max supply schedule is full")]
+    MaxSupplyScheduleFull,
+
+    #[msg("No scheduled max supply reduction is due")]
+    MaxSupplyScheduleNotDue,
+
+    #[msg("Invalid swap program")]
+    InvalidSwapProgram,
+
+    #[msg("Invalid reserve account")]
+    InvalidReserveAccount,
+
+    #[msg("Amount exceeds reserve surplus")]
+    InsufficientReserveSurplus,
+
+    #[msg("Swap returned less than the minimum amount")]
+    SlippageExceeded,
+
+    #[msg("Batch is empty or too large")]
+    InvalidBatchSize,
+
+    #[msg("Fee exceeds the allowed maximum")]
+    FeeTooHigh,
+
+    #[msg("Invalid volume tiers")]
+    InvalidVolumeTiers,
+
+    #[msg("Expiry must be in the future")]
+    InvalidExpiry,
+
+    #[msg("Transfer authority or delegate is blacklisted")]
+    DelegateBlacklisted,
+
+    #[msg("Token account is already frozen")]
+    AccountAlreadyFrozen,
+
+    #[msg("Token account is not frozen")]
+    AccountNotFrozen,
+
+    #[msg("Seizure target owner is not blacklisted")]
+    TargetNotBlacklisted,
+
+    #[msg("Address has no valid KYC record")]
+    KycRequired,
+
+    #[msg("Signer is not the KYC attester")]
+    UnauthorizedAttester,
+
+    #[msg("Invalid KYC tier")]
+    InvalidKycTier,
+
+    #[msg("KYC tier limit exceeded")]
+    KycLimitExceeded,
+
+    #[msg("Daily limit cannot exceed lifetime limit")]
+    InvalidKycLimits,
+
+    #[msg("Missing or invalid geo attestation")]
+    InvalidGeoAttestation,
+
+    #[msg("Geo attestation has expired")]
+    GeoAttestationExpired,
+
+    #[msg("Geo attestation nonce already used")]
+    GeoNonceReused,
+
+    #[msg("Jurisdiction is restricted")]
+    JurisdictionDenied,
+
+    #[msg("Too many denied jurisdictions")]
+    TooManyJurisdictions,
+
+    #[msg("Transfer hook called outside of a token transfer")]
+    NotTransferring,
+
+    #[msg("Mint is missing the required Token-2022 extension")]
+    MissingMintExtension,
+
+    #[msg("Token state PDA is not the mint authority")]
+    InvalidMintAuthority,
+
+    #[msg("Token state PDA is not the freeze authority")]
+    InvalidFreezeAuthority,
+
+    #[msg("Mint decimals do not match DECIMALS")]
+    InvalidMintDecimals,
+
+    #[msg("Mint supply must be zero at initialization")]
+    MintSupplyNotZero,
+
+    #[msg("Max supply has not been reached")]
+    MaxSupplyNotReached,
+
+    #[msg("Interest rate out of bounds")]
+    InterestRateOutOfBounds,
+
+    #[msg("No interest rate change is pending")]
+    NoPendingInterestRate,
+
+    #[msg("Interest rate timelock has not elapsed")]
+    InterestRateTimelocked,
+
+    #[msg("Reserve vault holds an asset that cannot be valued")]
+    UnsupportedReserveAsset,
+
+    #[msg("Reserve was attested too recently")]
+    ReserveAttestationTooSoon,
+
+    #[msg("Payment token must be USDT, USDC or wrapped SOL")]
+    UnsupportedPaymentToken,
+
+    #[msg("Token account is held frozen for another reason")]
+    AccountHeldFrozen,
+
+    #[msg("Too many KYC-exempt owners")]
+    TooManyKycExemptOwners,
+
+    #[msg("Token state PDA is not the interest rate authority")]
+    InvalidRateAuthority,
+
+    #[msg("Freeze-and-remint seizures need the target's freeze record")]
+    FreezeRecordRequired,
 }
@@ -1,5 +1,11 @@
 use anchor_lang::prelude::*;
 
+use crate::constants::KYC_TIER_COUNT;
+use crate::state::{
+    BlacklistReason, ComplianceMode, KycTier, MetadataField, ReserveAsset, SeizureMethod,
+    TierLimits, VolumeTier,
+};
+
 #[event]
 pub struct TokenStateInitialized {
     pub authority: Pubkey,
@@ -15,6 +21,7 @@ pub struct TokenMinted {
     pub amount: u64,
     pub payment_token: Pubkey,
     pub payment_amount: u64,
+    pub fee_amount: u64,
 }
 
 #[event]
@@ -24,6 +31,13 @@ pub struct TokenBurned {
     pub authority: Pubkey,
 }
 
+#[event]
+pub struct AccountBurned {
+    pub account: Pubkey,
+    pub amount: u64,
+    pub authority: Pubkey,
+}
+
 #[event]
 pub struct TokensPaused {
     pub timestamp: i64,
@@ -41,12 +55,54 @@ pub struct MaxSupplyUpdated {
     pub old_supply: u64,
     pub new_supply: u64,
     pub authority: Pubkey,
+    pub schedule_index: Option<u32>,
+}
+
+#[event]
+pub struct MaxSupplyScheduleExtended {
+    pub first_index: u32,
+    pub count: u32,
+    pub authority: Pubkey,
 }
 
 #[event]
 pub struct BlacklistUpdated {
     pub address: Pubkey,
     pub is_blacklisted: bool,
+    pub reason: BlacklistReason,
+    pub expires_at: Option<i64>,
+    pub added_by: Pubkey,
+    pub added_at: i64,
+    pub case_reference: [u8; 32],
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct AccountFrozen {
+    pub account: Pubkey,
+    pub owner: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AccountThawed {
+    pub account: Pubkey,
+    pub owner: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AutoFreezeUpdated {
+    pub enabled: bool,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct BlacklistMigrated {
+    pub migrated: u32,
+    pub remaining: u32,
     pub authority: Pubkey,
 }
 
@@ -54,21 +110,231 @@ pub struct BlacklistUpdated {
 pub struct ItemPurchased {
     pub buyer: Pubkey,
     pub amount: u64,
+    pub fee_amount: u64,
     pub timestamp: i64,
     pub vault_balance: u64,
 }
 
 #[event]
-pub struct TransferHookExecuted {
-    pub source: Pubkey,
+pub struct ReserveVerified {
+    pub total_supply: u64,
+    pub circulating_supply: u64,
+    pub liability_usd: u64,
+    pub reserve_usd: u64,
+    pub reserve_ratio_bps: u64,
+    pub assets: Vec<ReserveAsset>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReserveRecorded {
+    pub index: u16,
+    pub reserve_ratio_bps: u64,
+    pub caller: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReserveVaultsUpdated {
+    pub vaults: Vec<Pubkey>,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct BuybackConfigUpdated {
+    pub swap_program: Pubkey,
+    pub usdt_reserve: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct BuybackBurned {
+    pub usdt_spent: u64,
+    pub diamond_burned: u64,
+    pub surplus_before: u64,
+    pub swap_program: Pubkey,
+    pub new_total_supply: u64,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeesUpdated {
+    pub buy_fee_bps: u16,
+    pub redeem_fee_bps: u16,
+    pub purchase_fee_bps: u16,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct VolumeTiersUpdated {
+    pub tiers: Vec<VolumeTier>,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct FeesWithdrawn {
+    pub fee_account: Pubkey,
     pub destination: Pubkey,
     pub amount: u64,
+    pub authority: Pubkey,
 }
 
 #[event]
-pub struct ReserveVerified {
+pub struct TokensSeized {
+    pub case_reference: [u8; 32],
+    pub target_account: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub escrow: Pubkey,
+    pub method: SeizureMethod,
+    pub new_total_supply: u64,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ComplianceModeUpdated {
+    pub mode: ComplianceMode,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct KycAttesterUpdated {
+    pub attester: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct KycExemptOwnersUpdated {
+    pub owners: Vec<Pubkey>,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct KycRecordIssued {
+    pub wallet: Pubkey,
+    pub tier: KycTier,
+    pub expires_at: i64,
+    pub attester: Pubkey,
+    pub issued_at: i64,
+}
+
+#[event]
+pub struct KycTierUpdated {
+    pub wallet: Pubkey,
+    pub previous_tier: KycTier,
+    pub tier: KycTier,
+    pub expires_at: i64,
+    pub attester: Pubkey,
+}
+
+#[event]
+pub struct KycRecordRevoked {
+    pub wallet: Pubkey,
+    pub attester: Pubkey,
+}
+
+#[event]
+pub struct KycTierLimitsUpdated {
+    pub limits: [TierLimits; KYC_TIER_COUNT],
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct GeoAttestationConfigUpdated {
+    pub compliance_signer: Pubkey,
+    pub required: bool,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct DeniedJurisdictionsUpdated {
+    pub jurisdictions: Vec<[u8; 2]>,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct MetadataInitialized {
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct MetadataUpdated {
+    pub mint: Pubkey,
+    pub field: MetadataField,
+    pub value: String,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct TransferFeeUpdated {
+    pub basis_points: u16,
+    pub maximum_fee: u64,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct TransferFeesHarvested {
+    pub accounts: u32,
+    pub amount: u64,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct TransferFeesWithdrawn {
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct DefaultAccountStateUpdated {
+    pub frozen: bool,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct MintAuthorityRevoked {
+    pub mint: Pubkey,
     pub total_supply: u64,
-    pub expected_usdt: u64,
-    pub actual_usdt: u64,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfidentialAuditorUpdated {
+    pub auditor_elgamal_pubkey: Option<[u8; 32]>,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct ConfidentialAccountApproved {
+    pub account: Pubkey,
+    pub owner: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct InterestRateProposed {
+    pub rate_bps: i16,
+    pub executable_at: i64,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct InterestRateCancelled {
+    pub rate_bps: i16,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct InterestRateApplied {
+    pub rate_bps: i16,
+    pub cranker: Pubkey,
     pub timestamp: i64,
 }
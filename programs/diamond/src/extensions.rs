@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_2022::spl_token_2022::{self, extension::transfer_fee::TransferFeeConfig};
+use anchor_spl::token_interface;
+use anchor_spl::token_interface::spl_token_metadata_interface::state::Field;
+
+use crate::{error::*, state::MetadataField};
+
+/// Fails unless `mint` belongs to the Token-2022 program, which every
+/// extension instruction needs.
+pub fn require_token_2022(mint: &AccountInfo) -> Result<()> {
+    require_keys_eq!(
+        *mint.owner,
+        spl_token_2022::ID,
+        DiamondTokenError::MissingMintExtension
+    );
+
+    Ok(())
+}
+
+/// Fees withheld on the mint itself, waiting to be withdrawn.
+pub fn mint_withheld_fees(mint: &AccountInfo) -> Result<u64> {
+    let config = token_interface::get_mint_extension_data::<TransferFeeConfig>(mint)
+        .map_err(|_| DiamondTokenError::MissingMintExtension)?;
+
+    Ok(u64::from(config.withheld_amount))
+}
+
+/// Token-2022 grows the mint when variable-length extensions such as token
+/// metadata change, but does not fund the extra space. Tops the mint back up
+/// to rent exemption from `payer`.
+pub fn top_up_rent<'info>(
+    mint: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let required_lamports = Rent::get()?.minimum_balance(mint.data_len());
+    let current_lamports = mint.lamports();
+
+    if required_lamports > current_lamports {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: mint.clone(),
+                },
+            ),
+            required_lamports - current_lamports,
+        )?;
+    }
+
+    Ok(())
+}
+
+impl From<MetadataField> for Field {
+    fn from(field: MetadataField) -> Self {
+        match field {
+            MetadataField::Name => Field::Name,
+            MetadataField::Symbol => Field::Symbol,
+            MetadataField::Uri => Field::Uri,
+            MetadataField::Key(key) => Field::Key(key),
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{ed25519_program, sysvar::instructions};
+
+use crate::error::*;
+
+/// Prefix binding a signature to geo attestations, so the compliance signer's
+/// other signed messages can never be replayed as one.
+pub const GEO_ATTESTATION_DOMAIN: &[u8; 14] = b"DIAMOND_GEO_V1";
+
+/// domain (14) + program id (32) + user (32) + jurisdiction (2)
+/// + expires_at (8, LE) + nonce (8, LE)
+pub const GEO_ATTESTATION_LEN: usize = 14 + 32 + 32 + 2 + 8 + 8;
+
+// Layout of a single-signature ed25519 program instruction
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_LEN: usize = 14;
+const PUBKEY_LEN: usize = 32;
+const SIGNATURE_LEN: usize = 64;
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Geo-check result signed off-chain by the compliance signer.
+#[derive(Debug)]
+pub struct GeoAttestation {
+    pub user: Pubkey,
+    pub jurisdiction: [u8; 2],
+    pub expires_at: i64,
+    pub nonce: u64,
+}
+
+/// Reads the attestation from the ed25519 verify instruction directly before
+/// the current one, and checks it was signed by `signer`. The ed25519 program
+/// has already verified the signature, or the transaction would have failed.
+pub fn load_attestation(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+) -> Result<GeoAttestation> {
+    let current_index = instructions::load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, DiamondTokenError::InvalidGeoAttestation);
+
+    let ix = instructions::load_instruction_at_checked(
+        (current_index - 1) as usize,
+        instructions_sysvar,
+    )?;
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        DiamondTokenError::InvalidGeoAttestation
+    );
+
+    let data = &ix.data;
+    require!(
+        data.len() >= SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN && data[0] == 1,
+        DiamondTokenError::InvalidGeoAttestation
+    );
+
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let offsets = SIGNATURE_OFFSETS_START;
+    let signature_offset = read_u16(offsets) as usize;
+    let signature_ix = read_u16(offsets + 2);
+    let pubkey_offset = read_u16(offsets + 4) as usize;
+    let pubkey_ix = read_u16(offsets + 6);
+    let message_offset = read_u16(offsets + 8) as usize;
+    let message_len = read_u16(offsets + 10) as usize;
+    let message_ix = read_u16(offsets + 12);
+
+    // Everything must live in the ed25519 instruction itself, otherwise the
+    // verified bytes are not the ones read here
+    require!(
+        signature_ix == CURRENT_INSTRUCTION
+            && pubkey_ix == CURRENT_INSTRUCTION
+            && message_ix == CURRENT_INSTRUCTION
+            && message_len == GEO_ATTESTATION_LEN
+            && data.len() >= signature_offset + SIGNATURE_LEN
+            && data.len() >= pubkey_offset + PUBKEY_LEN
+            && data.len() >= message_offset + message_len,
+        DiamondTokenError::InvalidGeoAttestation
+    );
+
+    let pubkey = Pubkey::try_from(&data[pubkey_offset..pubkey_offset + PUBKEY_LEN])
+        .map_err(|_| DiamondTokenError::InvalidGeoAttestation)?;
+    require_keys_eq!(pubkey, *signer, DiamondTokenError::InvalidGeoAttestation);
+
+    parse_message(&data[message_offset..message_offset + message_len])
+}
+
+/// Decodes a signed attestation message, rejecting any not addressed to this
+/// program under the geo-attestation domain.
+pub fn parse_message(message: &[u8]) -> Result<GeoAttestation> {
+    require!(
+        message.len() == GEO_ATTESTATION_LEN,
+        DiamondTokenError::InvalidGeoAttestation
+    );
+
+    let (domain, rest) = message.split_at(GEO_ATTESTATION_DOMAIN.len());
+    let (program_id, body) = rest.split_at(32);
+    require!(
+        domain == GEO_ATTESTATION_DOMAIN && program_id == crate::ID.as_ref(),
+        DiamondTokenError::InvalidGeoAttestation
+    );
+
+    let mut expires_at = [0u8; 8];
+    expires_at.copy_from_slice(&body[34..42]);
+    let mut nonce = [0u8; 8];
+    nonce.copy_from_slice(&body[42..50]);
+
+    Ok(GeoAttestation {
+        user: Pubkey::try_from(&body[0..32])
+            .map_err(|_| DiamondTokenError::InvalidGeoAttestation)?,
+        jurisdiction: [body[32], body[33]],
+        expires_at: i64::from_le_bytes(expires_at),
+        nonce: u64::from_le_bytes(nonce),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(domain: &[u8], program_id: &Pubkey) -> Vec<u8> {
+        let mut message = Vec::with_capacity(GEO_ATTESTATION_LEN);
+        message.extend_from_slice(domain);
+        message.extend_from_slice(program_id.as_ref());
+        message.extend_from_slice(Pubkey::new_from_array([7; 32]).as_ref());
+        message.extend_from_slice(b"US");
+        message.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+        message.extend_from_slice(&42u64.to_le_bytes());
+        message
+    }
+
+    #[test]
+    fn parse_message_reads_fields() {
+        let attestation = parse_message(&message(GEO_ATTESTATION_DOMAIN, &crate::ID)).unwrap();
+        assert_eq!(attestation.user, Pubkey::new_from_array([7; 32]));
+        assert_eq!(&attestation.jurisdiction, b"US");
+        assert_eq!(attestation.expires_at, 1_700_000_000);
+        assert_eq!(attestation.nonce, 42);
+    }
+
+    #[test]
+    fn parse_message_rejects_other_domains() {
+        assert_eq!(
+            parse_message(&message(b"DIAMOND_KYC_V1", &crate::ID)).unwrap_err(),
+            DiamondTokenError::InvalidGeoAttestation.into()
+        );
+        assert_eq!(
+            parse_message(&message(GEO_ATTESTATION_DOMAIN, &Pubkey::new_unique())).unwrap_err(),
+            DiamondTokenError::InvalidGeoAttestation.into()
+        );
+
+        let mut short = message(GEO_ATTESTATION_DOMAIN, &crate::ID);
+        short.pop();
+        assert_eq!(
+            parse_message(&short).unwrap_err(),
+            DiamondTokenError::InvalidGeoAttestation.into()
+        );
+    }
+}
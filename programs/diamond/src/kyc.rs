@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+/// Tier attested by the `KycRecord` at `record`, `Unverified` when it does not
+/// exist or has expired. Callers must have checked that `record` is the PDA
+/// for the wallet.
+pub fn record_tier(record: &AccountInfo, now: i64) -> Result<KycTier> {
+    if record.owner != &crate::ID || record.data_is_empty() {
+        return Ok(KycTier::Unverified);
+    }
+
+    let data = record.try_borrow_data()?;
+    let record = KycRecord::try_deserialize(&mut &data[..])?;
+
+    Ok(record.tier_at(now))
+}
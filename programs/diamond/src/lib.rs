@@ -2,18 +2,38 @@ use anchor_lang::prelude::*;
 use anchor_lang::solana_program::clock::Clock;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Token, Transfer, Mint, TokenAccount},
+    token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
-use pyth_sdk_solana::state::SolanaPriceAccount;
 
 declare_id!("97xUm7Kv6TiKyCkaLGgmTFu3skVte3wStYY4vYTXtpxL");
 
+pub mod blacklist;
+pub mod compliance;
 pub mod constants;
 pub mod error;
 pub mod events;
+pub mod extensions;
+pub mod geo;
+pub mod kyc;
+pub mod pricing;
+pub mod reserve;
 pub mod state;
+pub mod swap;
+
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{
+        confidential_transfer::{self, ConfidentialTransferMint},
+        interest_bearing_mint::InterestBearingConfig,
+        metadata_pointer::{self, MetadataPointer},
+    },
+    instruction::AuthorityType,
+    onchain::invoke_transfer_checked,
+    solana_zk_sdk::encryption::pod::elgamal::PodElGamalPubkey,
+    state::AccountState,
+};
 
-use crate::{constants::*, error::*, events::*, state::*};
+use crate::{constants::*, error::*, events::*, pricing::MintQuote, state::*};
 
 #[program]
 pub mod diamond {
@@ -30,6 +50,23 @@ pub mod diamond {
             DiamondTokenError::InvalidMultisigThreshold
         );
 
+        // The token state PDA must control a fresh mint with the expected decimals
+        let token_state_key = ctx.accounts.token_state.key();
+        let mint = &ctx.accounts.mint;
+        require!(
+            Option::<Pubkey>::from(mint.mint_authority) == Some(token_state_key),
+            DiamondTokenError::InvalidMintAuthority
+        );
+        require!(
+            Option::<Pubkey>::from(mint.freeze_authority) == Some(token_state_key),
+            DiamondTokenError::InvalidFreezeAuthority
+        );
+        require!(
+            mint.decimals == DECIMALS,
+            DiamondTokenError::InvalidMintDecimals
+        );
+        require!(mint.supply == 0, DiamondTokenError::MintSupplyNotZero);
+
         // Initialize token state
         let token_state = &mut ctx.accounts.token_state;
         token_state.authority = ctx.accounts.payer.key();
@@ -40,20 +77,16 @@ pub mod diamond {
         token_state.last_pause_timestamp = 0;
         token_state.multisig = ctx.accounts.multisig.key();
         token_state.vault = ctx.accounts.vault.key();
+        token_state.seized_locked = 0;
         token_state.bump = ctx.bumps.token_state;
 
-        // Initialize blacklist
-        let blacklist = &mut ctx.accounts.blacklist;
-        blacklist.addresses = Vec::new();
-        blacklist.bump = ctx.bumps.blacklist;
-
         // Mint initial supply to vault
         let token_state_seeds = &[TOKEN_STATE_SEED, &[token_state.bump]];
         let signer = &[&token_state_seeds[..]];
 
         let mint_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            anchor_spl::token::MintTo {
+            token_interface::MintTo {
                 mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.vault.to_account_info(),
                 authority: token_state.to_account_info(),
@@ -61,7 +94,7 @@ pub mod diamond {
             signer,
         );
 
-        anchor_spl::token::mint_to(mint_ctx, INITIAL_SUPPLY)?;
+        token_interface::mint_to(mint_ctx, INITIAL_SUPPLY)?;
 
         emit!(TokenStateInitialized {
             authority: token_state.authority,
@@ -80,61 +113,77 @@ pub mod diamond {
         // Validate amount is not zero
         require!(amount > 0, DiamondTokenError::InvalidAmount);
 
-        // Check blacklist
-        let blacklist = &ctx.accounts.blacklist;
-        require!(
-            !blacklist.addresses.contains(&ctx.accounts.user.key()),
-            DiamondTokenError::AddressBlacklisted
-        );
+        // Check blacklist and, in allow-list mode, KYC
+        let current_time = Clock::get()?.unix_timestamp;
+        compliance::require_not_blacklisted(
+            &ctx.accounts.user_blacklist_entry,
+            current_time,
+            DiamondTokenError::AddressBlacklisted,
+        )?;
+        compliance::require_kyc_verified(
+            &ctx.accounts.config,
+            &ctx.accounts.user_kyc_record,
+            current_time,
+        )?;
+
+        // Enforce the daily and lifetime limits of the user's KYC tier
+        let tier = kyc::record_tier(&ctx.accounts.user_kyc_record, current_time)?;
+        let user_volume = &mut ctx.accounts.user_volume;
+        user_volume.wallet = ctx.accounts.user.key();
+        user_volume.bump = ctx.bumps.user_volume;
+        user_volume.record(amount, ctx.accounts.config.tier_limits(tier), current_time)?;
+
+        // Check the off-chain geo attestation, if required
+        if ctx.accounts.config.require_geo_attestation {
+            let attestation = geo::load_attestation(
+                &ctx.accounts.instructions,
+                &ctx.accounts.config.compliance_signer,
+            )?;
+            require_keys_eq!(
+                attestation.user,
+                ctx.accounts.user.key(),
+                DiamondTokenError::InvalidGeoAttestation
+            );
+            require!(
+                attestation.expires_at > current_time,
+                DiamondTokenError::GeoAttestationExpired
+            );
+            require!(
+                !ctx.accounts
+                    .config
+                    .denied_jurisdictions
+                    .contains(&attestation.jurisdiction),
+                DiamondTokenError::JurisdictionDenied
+            );
+            ctx.accounts.user_volume.use_geo_nonce(attestation.nonce)?;
+        }
 
         // Validate token accounts
         require!(
             ctx.accounts.user_token_account.mint == ctx.accounts.mint.key(),
             DiamondTokenError::InvalidTokenAccount
         );
+        // Minted tokens go to the checked wallet, not to an arbitrary owner
+        require!(
+            ctx.accounts.user_token_account.owner == ctx.accounts.user.key(),
+            DiamondTokenError::InvalidTokenAccount
+        );
         require!(
             ctx.accounts.user_payment_account.mint == ctx.accounts.payment_token.key(),
             DiamondTokenError::InvalidTokenAccount
         );
 
-        // Calculate payment amount based on token type
-        let payment_amount = match ctx.accounts.payment_token.decimals {
-            6 => { // USDT or USDC
-                let amount = if ctx.accounts.payment_token.key() == USDT_PUBKEY {
-                    amount.checked_mul(TOKEN_PRICE_USDT)
-                } else {
-                    amount.checked_mul(TOKEN_PRICE_USDC)
-                }.ok_or(DiamondTokenError::MathOverflow)?;
-                
-                require!(
-                    amount >= MIN_PURCHASE_USDC,
-                    DiamondTokenError::PurchaseAmountTooSmall
-                );
-                amount
-            }
-            SOL_DECIMALS => {
-                // Get SOL price from Pyth oracle
-                let price_feed = SolanaPriceAccount::account_info_to_feed(&ctx.accounts.sol_price_feed)
-                    .map_err(|_| DiamondTokenError::InvalidPriceFeed)?;
-
-                let current_time = Clock::get()?.unix_timestamp;
-                let current_price = price_feed.get_price_no_older_than(current_time, MAX_PRICE_AGE as u64)
-                    .ok_or(DiamondTokenError::InvalidPriceFeed)?;
-
-                // Calculate SOL amount needed
-                let token_price_usd = 0.8; // 0.8 USD per token
-                let sol_price_usd = current_price.price as f64 / 10f64.powi(current_price.expo as i32);
-                let sol_amount = (amount as f64 * token_price_usd / sol_price_usd * 1e9) as u64;
-
-                require!(
-                    sol_amount >= MIN_PURCHASE_SOL,
-                    DiamondTokenError::PurchaseAmountTooSmall
-                );
-
-                sol_amount
-            }
-            _ => return Err(DiamondTokenError::InvalidDecimals.into()),
-        };
+        // Price the mint, including volume discount and buy fee
+        let MintQuote {
+            payment_amount,
+            fee_amount,
+            ..
+        } = pricing::quote_mint(
+            amount,
+            &ctx.accounts.payment_token,
+            ctx.accounts.sol_price_feed.as_deref(),
+            &ctx.accounts.config,
+        )?;
 
         // Check if minting would exceed max supply
         let new_supply = token_state
@@ -143,21 +192,45 @@ pub mod diamond {
             .ok_or(DiamondTokenError::MathOverflow)?;
 
         require!(
-            new_supply <= token_state.max_supply,
+            new_supply.saturating_sub(token_state.seized_locked) <= token_state.max_supply,
             DiamondTokenError::MaxSupplyExceeded
         );
 
         // Transfer payment to vault
         let transfer_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.payment_token_program.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.user_payment_account.to_account_info(),
+                mint: ctx.accounts.payment_token.to_account_info(),
                 to: ctx.accounts.vault.to_account_info(),
                 authority: ctx.accounts.user.to_account_info(),
             },
         );
 
-        anchor_spl::token::transfer(transfer_ctx, payment_amount)?;
+        token_interface::transfer_checked(
+            transfer_ctx,
+            payment_amount,
+            ctx.accounts.payment_token.decimals,
+        )?;
+
+        // Transfer fee to the fee treasury
+        if fee_amount > 0 {
+            let fee_ctx = CpiContext::new(
+                ctx.accounts.payment_token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_payment_account.to_account_info(),
+                    mint: ctx.accounts.payment_token.to_account_info(),
+                    to: ctx.accounts.fee_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            );
+
+            token_interface::transfer_checked(
+                fee_ctx,
+                fee_amount,
+                ctx.accounts.payment_token.decimals,
+            )?;
+        }
 
         // Mint tokens to user
         let token_state_seeds = &[TOKEN_STATE_SEED, &[token_state.bump]];
@@ -165,7 +238,7 @@ pub mod diamond {
 
         let mint_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            anchor_spl::token::MintTo {
+            token_interface::MintTo {
                 mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.user_token_account.to_account_info(),
                 authority: token_state.to_account_info(),
@@ -173,7 +246,7 @@ pub mod diamond {
             signer,
         );
 
-        anchor_spl::token::mint_to(mint_ctx, amount)?;
+        token_interface::mint_to(mint_ctx, amount)?;
 
         // Update state
         token_state.total_supply = new_supply;
@@ -184,6 +257,7 @@ pub mod diamond {
             amount,
             payment_token: ctx.accounts.payment_token.key(),
             payment_amount,
+            fee_amount,
         });
 
         Ok(())
@@ -203,18 +277,29 @@ pub mod diamond {
         // Verify amount
         require!(amount > 0, DiamondTokenError::InvalidAmount);
 
+        // Refunds must not reach a blacklisted wallet
+        compliance::require_not_blacklisted(
+            &ctx.accounts.refund_owner_blacklist_entry,
+            Clock::get()?.unix_timestamp,
+            DiamondTokenError::DestinationAddressBlacklisted,
+        )?;
+
         // Verify vault has enough tokens
         require!(vault.amount >= amount, DiamondTokenError::InsufficientFunds);
 
+        let token_state_seeds = &[TOKEN_STATE_SEED, &[token_state.bump]];
+        let signer = &[&token_state_seeds[..]];
+
         // Burn tokens from vault
-        anchor_spl::token::burn(
-            CpiContext::new(
+        token_interface::burn(
+            CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                anchor_spl::token::Burn {
+                token_interface::Burn {
                     mint: mint.to_account_info(),
                     from: vault.to_account_info(),
                     authority: token_state.to_account_info(),
                 },
+                signer,
             ),
             amount,
         )?;
@@ -228,14 +313,15 @@ pub mod diamond {
         // If premint account exists, burn from it too
         if let Some(premint) = &ctx.accounts.premint_account {
             if premint.amount > 0 {
-                anchor_spl::token::burn(
-                    CpiContext::new(
+                token_interface::burn(
+                    CpiContext::new_with_signer(
                         ctx.accounts.token_program.to_account_info(),
-                        anchor_spl::token::Burn {
+                        token_interface::Burn {
                             mint: mint.to_account_info(),
                             from: premint.to_account_info(),
                             authority: token_state.to_account_info(),
                         },
+                        signer,
                     ),
                     premint.amount,
                 )?;
@@ -251,6 +337,77 @@ pub mod diamond {
         Ok(())
     }
 
+    pub fn batch_admin_burn<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchAdminBurn<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.admin.key())?;
+
+        // One amount per source account, bounded to stay within compute limits
+        require!(
+            !amounts.is_empty()
+                && amounts.len() <= MAX_BATCH_BURN_ACCOUNTS
+                && amounts.len() == ctx.remaining_accounts.len(),
+            DiamondTokenError::InvalidBatchSize
+        );
+
+        let token_state_seeds = &[TOKEN_STATE_SEED, &[ctx.accounts.token_state.bump]];
+        let signer = &[&token_state_seeds[..]];
+
+        let mut total_burned: u64 = 0;
+
+        for (source_info, &amount) in ctx.remaining_accounts.iter().zip(amounts.iter()) {
+            require!(amount > 0, DiamondTokenError::InvalidAmount);
+
+            // Source must be a DIAMOND account owned by the token state PDA
+            let source = InterfaceAccount::<TokenAccount>::try_from(source_info)?;
+            require!(
+                source.mint == ctx.accounts.token_state.mint
+                    && source.owner == ctx.accounts.token_state.key(),
+                DiamondTokenError::InvalidTokenAccount
+            );
+            require!(
+                source.amount >= amount,
+                DiamondTokenError::InsufficientFunds
+            );
+
+            token_interface::burn(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_interface::Burn {
+                        mint: ctx.accounts.mint.to_account_info(),
+                        from: source_info.clone(),
+                        authority: ctx.accounts.token_state.to_account_info(),
+                    },
+                    signer,
+                ),
+                amount,
+            )?;
+
+            total_burned = total_burned
+                .checked_add(amount)
+                .ok_or(DiamondTokenError::MathOverflow)?;
+
+            emit!(AccountBurned {
+                account: source_info.key(),
+                amount,
+                authority: ctx.accounts.admin.key(),
+            });
+        }
+
+        // Update total supply
+        let token_state = &mut ctx.accounts.token_state;
+        token_state.total_supply = token_state
+            .total_supply
+            .checked_sub(total_burned)
+            .ok_or(DiamondTokenError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
     pub fn pause(ctx: Context<Pause>) -> Result<()> {
         let token_state = &mut ctx.accounts.token_state;
 
@@ -310,7 +467,7 @@ pub mod diamond {
 
         // Check if new supply is not less than current total supply
         require!(
-            new_max_supply >= token_state.total_supply,
+            new_max_supply >= token_state.effective_supply(),
             DiamondTokenError::InvalidMaxSupply
         );
 
@@ -342,288 +499,2961 @@ pub mod diamond {
             old_supply,
             new_supply: new_max_supply,
             authority: ctx.accounts.authority.key(),
+            schedule_index: None,
         });
 
         Ok(())
     }
 
-    pub fn add_to_blacklist(ctx: Context<UpdateBlacklist>, address: Pubkey) -> Result<()> {
-        let blacklist = &mut ctx.accounts.blacklist;
+    pub fn initialize_max_supply_schedule(
+        ctx: Context<InitializeMaxSupplySchedule>,
+        reductions: Vec<ScheduledReduction>,
+    ) -> Result<()> {
+        let token_state = &ctx.accounts.token_state;
 
-        // Check if address is already blacklisted
-        require!(
-            !blacklist.addresses.contains(&address),
-            DiamondTokenError::AddressAlreadyBlacklisted
-        );
+        // Verify admin signature
+        token_state.require_admin(&ctx.accounts.authority.key())?;
+
+        let schedule = &mut ctx.accounts.max_supply_schedule;
+        schedule.reductions = Vec::new();
+        schedule.next_index = 0;
+        schedule.bump = ctx.bumps.max_supply_schedule;
+
+        schedule.append(
+            &reductions,
+            token_state.max_supply,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        emit!(MaxSupplyScheduleExtended {
+            first_index: 0,
+            count: reductions.len() as u32,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn extend_max_supply_schedule(
+        ctx: Context<ExtendMaxSupplySchedule>,
+        reductions: Vec<ScheduledReduction>,
+    ) -> Result<()> {
+        let token_state = &ctx.accounts.token_state;
+
+        // Verify admin signature
+        token_state.require_admin(&ctx.accounts.authority.key())?;
+
+        let schedule = &mut ctx.accounts.max_supply_schedule;
+        let first_index = schedule.reductions.len() as u32;
+
+        schedule.append(
+            &reductions,
+            token_state.max_supply,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        emit!(MaxSupplyScheduleExtended {
+            first_index,
+            count: reductions.len() as u32,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
 
-        // Check if blacklist is at capacity
+    pub fn crank_max_supply_schedule(ctx: Context<CrankMaxSupplySchedule>) -> Result<()> {
+        let token_state = &mut ctx.accounts.token_state;
+        let schedule = &mut ctx.accounts.max_supply_schedule;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let mut applied = false;
+
+        // Apply every reduction whose timestamp has passed
+        while let Some(reduction) = schedule.reductions.get(schedule.next_index as usize) {
+            if reduction.timestamp > current_time {
+                break;
+            }
+
+            // Never go below what is already minted
+            let new_max_supply = reduction.max_supply.max(token_state.effective_supply());
+
+            // A manual reduction may already have gone further
+            if new_max_supply < token_state.max_supply {
+                let old_supply = token_state.max_supply;
+                token_state.max_supply = new_max_supply;
+
+                emit!(MaxSupplyUpdated {
+                    old_supply,
+                    new_supply: new_max_supply,
+                    authority: ctx.accounts.cranker.key(),
+                    schedule_index: Some(schedule.next_index),
+                });
+            }
+
+            schedule.next_index = schedule
+                .next_index
+                .checked_add(1)
+                .ok_or(DiamondTokenError::MathOverflow)?;
+            applied = true;
+        }
+
+        require!(applied, DiamondTokenError::MaxSupplyScheduleNotDue);
+
+        Ok(())
+    }
+
+    /// With auto-freeze on, remaining accounts are (DIAMOND token account,
+    /// `FreezeRecord` PDA) pairs for the wallet. Only the accounts passed are
+    /// frozen, so callers must pass every account the wallet holds; on
+    /// Token-2022 mints the transfer hook blocks the wallet's other accounts.
+    pub fn add_to_blacklist<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AddToBlacklist<'info>>,
+        address: Pubkey,
+        reason: BlacklistReason,
+        expires_at: Option<i64>,
+        case_reference: [u8; 32],
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        let current_time = Clock::get()?.unix_timestamp;
+        if let Some(expires_at) = expires_at {
+            require!(expires_at > current_time, DiamondTokenError::InvalidExpiry);
+        }
+
+        // Creating the entry PDA is what blacklists the address. init_if_needed
+        // leaves a new entry zeroed; an existing one may only be refreshed once
+        // it has expired.
+        let entry = &mut ctx.accounts.blacklist_entry;
         require!(
-            blacklist.addresses.len() < 100, // Maximum 100 blacklisted addresses
-            DiamondTokenError::BlacklistFull
+            entry.added_at == 0 || !entry.is_active(current_time),
+            DiamondTokenError::AddressAlreadyBlacklisted
         );
-
-        // Add address to blacklist
-        blacklist.addresses.push(address);
+        entry.set_inner(BlacklistEntry {
+            address,
+            reason,
+            expires_at,
+            added_by: ctx.accounts.authority.key(),
+            added_at: current_time,
+            case_reference,
+            bump: ctx.bumps.blacklist_entry,
+        });
 
         // Emit event
         emit!(BlacklistUpdated {
             address,
             is_blacklisted: true,
+            reason,
+            expires_at,
+            added_by: entry.added_by,
+            added_at: entry.added_at,
+            case_reference,
             authority: ctx.accounts.authority.key(),
         });
 
+        // Freeze the wallet's DIAMOND accounts passed as remaining accounts
+        if ctx.accounts.config.auto_freeze_on_blacklist {
+            let token_state_seeds = &[TOKEN_STATE_SEED, &[ctx.accounts.token_state.bump]];
+            let signer = &[&token_state_seeds[..]];
+
+            compliance::freeze_blacklisted_accounts(
+                ctx.remaining_accounts,
+                &[address],
+                &ctx.accounts.mint.to_account_info(),
+                &ctx.accounts.token_state.to_account_info(),
+                &ctx.accounts.token_program.to_account_info(),
+                &ctx.accounts.authority.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                signer,
+            )?;
+        }
+
         Ok(())
     }
 
-    pub fn remove_from_blacklist(ctx: Context<UpdateBlacklist>, address: Pubkey) -> Result<()> {
-        let blacklist = &mut ctx.accounts.blacklist;
-
-        // Check if address is in blacklist
-        require!(
-            blacklist.addresses.contains(&address),
-            DiamondTokenError::AddressNotBlacklisted
-        );
+    pub fn remove_from_blacklist<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RemoveFromBlacklist<'info>>,
+        address: Pubkey,
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
 
-        // Remove address from blacklist
-        if let Some(index) = blacklist.addresses.iter().position(|x| x == &address) {
-            blacklist.addresses.remove(index);
-        }
+        // The entry PDA is closed by the account constraints
+        let entry = &ctx.accounts.blacklist_entry;
 
         // Emit event
         emit!(BlacklistUpdated {
             address,
             is_blacklisted: false,
+            reason: entry.reason,
+            expires_at: entry.expires_at,
+            added_by: entry.added_by,
+            added_at: entry.added_at,
+            case_reference: entry.case_reference,
             authority: ctx.accounts.authority.key(),
         });
 
+        // Thaw the wallet's DIAMOND accounts that auto-freeze froze
+        if ctx.accounts.config.auto_freeze_on_blacklist {
+            let token_state_seeds = &[TOKEN_STATE_SEED, &[ctx.accounts.token_state.bump]];
+            let signer = &[&token_state_seeds[..]];
+
+            compliance::thaw_blacklisted_accounts(
+                ctx.remaining_accounts,
+                &[address],
+                &ctx.accounts.mint.to_account_info(),
+                &ctx.accounts.token_state.to_account_info(),
+                &ctx.accounts.token_program.to_account_info(),
+                signer,
+            )?;
+        }
+
         Ok(())
     }
 
-    pub fn purchase_item(ctx: Context<PurchaseItem>, amount: u64) -> Result<()> {
-        // Check if token operations are paused
+    pub fn set_auto_freeze(ctx: Context<SetAutoFreeze>, enabled: bool) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        ctx.accounts.config.auto_freeze_on_blacklist = enabled;
+
+        emit!(AutoFreezeUpdated {
+            enabled,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn set_compliance_mode(
+        ctx: Context<SetComplianceMode>,
+        mode: ComplianceMode,
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        ctx.accounts.config.compliance_mode = mode;
+
+        emit!(ComplianceModeUpdated {
+            mode,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn set_kyc_attester(ctx: Context<SetKycAttester>, attester: Pubkey) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        ctx.accounts.config.kyc_attester = attester;
+
+        emit!(KycAttesterUpdated {
+            attester,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Owners whose DIAMOND accounts may receive transfers in allow-list mode
+    /// without a KYC record, such as AMM pool authorities.
+    pub fn set_kyc_exempt_owners(
+        ctx: Context<SetKycExemptOwners>,
+        owners: Vec<Pubkey>,
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
         require!(
-            !ctx.accounts.token_state.is_paused,
-            DiamondTokenError::Paused
+            owners.len() <= MAX_KYC_EXEMPT_OWNERS,
+            DiamondTokenError::TooManyKycExemptOwners
         );
 
-        // Check if user has sufficient balance
+        ctx.accounts.config.kyc_exempt_owners = owners.clone();
+
+        emit!(KycExemptOwnersUpdated {
+            owners,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn issue_kyc_record(
+        ctx: Context<IssueKycRecord>,
+        wallet: Pubkey,
+        tier: KycTier,
+        expires_at: i64,
+    ) -> Result<()> {
         require!(
-            ctx.accounts.user_token_account.amount >= amount,
-            DiamondTokenError::InsufficientBalance
+            tier > KycTier::Unverified,
+            DiamondTokenError::InvalidKycTier
         );
 
-        // Check minimum purchase amount
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(expires_at > current_time, DiamondTokenError::InvalidExpiry);
+
+        ctx.accounts.kyc_record.set_inner(KycRecord {
+            wallet,
+            tier,
+            expires_at,
+            attester: ctx.accounts.attester.key(),
+            issued_at: current_time,
+            bump: ctx.bumps.kyc_record,
+        });
+
+        emit!(KycRecordIssued {
+            wallet,
+            tier,
+            expires_at,
+            attester: ctx.accounts.attester.key(),
+            issued_at: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn update_kyc_tier(
+        ctx: Context<UpdateKycRecord>,
+        wallet: Pubkey,
+        tier: KycTier,
+        expires_at: i64,
+    ) -> Result<()> {
+        // Dropping to unverified is a revocation
         require!(
-            amount >= MIN_PURCHASE_AMOUNT,
-            DiamondTokenError::PurchaseAmountTooSmall
+            tier > KycTier::Unverified,
+            DiamondTokenError::InvalidKycTier
         );
-
-        // Transfer tokens to vault
-        let transfer_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.user_token_account.to_account_info(),
-                to: ctx.accounts.vault.to_account_info(),
-                authority: ctx.accounts.user.to_account_info(),
-            },
+        require!(
+            expires_at > Clock::get()?.unix_timestamp,
+            DiamondTokenError::InvalidExpiry
         );
 
-        anchor_spl::token::transfer(transfer_ctx, amount)?;
-
-        // Emit event with more details
-        emit!(ItemPurchased {
-            buyer: ctx.accounts.user.key(),
-            amount,
-            timestamp: Clock::get()?.unix_timestamp,
-            vault_balance: ctx.accounts.vault.amount,
+        let record = &mut ctx.accounts.kyc_record;
+        let previous_tier = record.tier;
+        record.tier = tier;
+        record.expires_at = expires_at;
+        record.attester = ctx.accounts.attester.key();
+
+        emit!(KycTierUpdated {
+            wallet,
+            previous_tier,
+            tier,
+            expires_at,
+            attester: ctx.accounts.attester.key(),
         });
 
         Ok(())
     }
 
-    pub fn on_transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
-        let blacklist = &ctx.accounts.blacklist;
+    pub fn revoke_kyc_record(ctx: Context<RevokeKycRecord>, wallet: Pubkey) -> Result<()> {
+        emit!(KycRecordRevoked {
+            wallet,
+            attester: ctx.accounts.attester.key(),
+        });
 
-        // Check if source is blacklisted
-        if blacklist.addresses.contains(&ctx.accounts.source.key()) {
-            return Err(DiamondTokenError::SourceAddressBlacklisted.into());
-        }
+        Ok(())
+    }
 
-        // Check if destination is blacklisted
-        if blacklist
-            .addresses
-            .contains(&ctx.accounts.destination.key())
-        {
-            return Err(DiamondTokenError::DestinationAddressBlacklisted.into());
+    pub fn set_kyc_tier_limits(
+        ctx: Context<SetKycTierLimits>,
+        limits: [TierLimits; KYC_TIER_COUNT],
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        for tier_limits in limits.iter() {
+            require!(
+                tier_limits.daily_limit <= tier_limits.lifetime_limit,
+                DiamondTokenError::InvalidKycLimits
+            );
         }
 
-        // Emit event for successful transfer
-        emit!(TransferHookExecuted {
-            source: ctx.accounts.source.key(),
-            destination: ctx.accounts.destination.key(),
-            amount,
+        ctx.accounts.config.kyc_tier_limits = limits;
+
+        emit!(KycTierLimitsUpdated {
+            limits,
+            authority: ctx.accounts.authority.key(),
         });
 
         Ok(())
     }
 
-    pub fn verify_reserve(ctx: Context<VerifyReserve>) -> Result<()> {
-        let token_state = &ctx.accounts.token_state;
-        let vault = &ctx.accounts.vault;
-
-        // Calculate expected USDT balance based on total supply
-        let expected_usdt = token_state
-            .total_supply
-            .checked_mul(TOKEN_PRICE_USDT)
-            .ok_or(DiamondTokenError::MathOverflow)?;
+    pub fn revoke_mint_authority(ctx: Context<RevokeMintAuthority>) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
 
-        // Verify vault has sufficient USDT balance
+        let token_state = &ctx.accounts.token_state;
         require!(
-            vault.amount >= expected_usdt,
-            DiamondTokenError::InsufficientReserve
+            token_state.effective_supply() >= token_state.max_supply,
+            DiamondTokenError::MaxSupplyNotReached
         );
 
-        // Emit verification event
-        emit!(ReserveVerified {
+        let token_state_seeds = &[TOKEN_STATE_SEED, &[token_state.bump]];
+        let signer = &[&token_state_seeds[..]];
+
+        // Irreversible: no one can mint DIAMOND afterwards
+        token_interface::set_authority(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::SetAuthority {
+                    current_authority: token_state.to_account_info(),
+                    account_or_mint: ctx.accounts.mint.to_account_info(),
+                },
+                signer,
+            ),
+            AuthorityType::MintTokens,
+            None,
+        )?;
+
+        emit!(MintAuthorityRevoked {
+            mint: ctx.accounts.mint.key(),
             total_supply: token_state.total_supply,
-            expected_usdt,
-            actual_usdt: vault.amount,
+            authority: ctx.accounts.authority.key(),
             timestamp: Clock::get()?.unix_timestamp,
         });
 
         Ok(())
     }
-}
-
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
 
-    #[account(
-        init,
-        payer = payer,
-        space = TokenState::LEN,
-        seeds = [TOKEN_STATE_SEED],
-        bump
-    )]
-    pub token_state: Account<'info, TokenState>,
+    pub fn set_default_account_frozen(
+        ctx: Context<SetDefaultAccountState>,
+        frozen: bool,
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
 
-    pub mint: Account<'info, Mint>,
+        let mint = ctx.accounts.mint.to_account_info();
+        extensions::require_token_2022(&mint)?;
 
-    #[account(
-        mut,
-        seeds = [VAULT_SEED],
-        bump
-    )]
-    pub vault: Account<'info, TokenAccount>,
+        let token_state_seeds = &[TOKEN_STATE_SEED, &[ctx.accounts.token_state.bump]];
+        let signer = &[&token_state_seeds[..]];
 
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub rent: Sysvar<'info, Rent>,
+        let state = if frozen {
+            AccountState::Frozen
+        } else {
+            AccountState::Initialized
+        };
+        token_interface::default_account_state_update(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::DefaultAccountStateUpdate {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint,
+                    freeze_authority: ctx.accounts.token_state.to_account_info(),
+                },
+                signer,
+            ),
+            &state,
+        )?;
+
+        emit!(DefaultAccountStateUpdated {
+            frozen,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    /// With the mint's default account state set to frozen, new accounts
+    /// stay frozen until their owner passes KYC and is not blacklisted.
+    /// Anyone may crank this once those hold, but only for accounts frozen by
+    /// default: a freeze by governance, auto-freeze or seizure is recorded
+    /// in the account's `FreezeRecord` and is left to its own release.
+    pub fn thaw_verified_account(ctx: Context<ThawVerifiedAccount>) -> Result<()> {
+        require!(
+            ctx.accounts.token_account.is_frozen(),
+            DiamondTokenError::AccountNotFrozen
+        );
+        if let Some(record) = compliance::load_freeze_record(&ctx.accounts.freeze_record)? {
+            require!(!record.is_held(), DiamondTokenError::AccountHeldFrozen);
+        }
+
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            kyc::record_tier(&ctx.accounts.owner_kyc_record, current_time)? > KycTier::Unverified,
+            DiamondTokenError::KycRequired
+        );
+        compliance::require_not_blacklisted(
+            &ctx.accounts.owner_blacklist_entry,
+            current_time,
+            DiamondTokenError::AddressBlacklisted,
+        )?;
+
+        let token_state_seeds = &[TOKEN_STATE_SEED, &[ctx.accounts.token_state.bump]];
+        let signer = &[&token_state_seeds[..]];
+
+        compliance::set_frozen(
+            &ctx.accounts.token_account.to_account_info(),
+            false,
+            &ctx.accounts.mint.to_account_info(),
+            &ctx.accounts.token_state.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            signer,
+        )?;
+
+        emit!(AccountThawed {
+            account: ctx.accounts.token_account.key(),
+            owner: ctx.accounts.token_account.owner,
+            authority: ctx.accounts.caller.key(),
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_confidential_transfer_auditor(
+        ctx: Context<SetConfidentialTransferAuditor>,
+        auditor_elgamal_pubkey: Option<[u8; 32]>,
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        let mint = ctx.accounts.mint.to_account_info();
+        extensions::require_token_2022(&mint)?;
+        token_interface::get_mint_extension_data::<ConfidentialTransferMint>(&mint)
+            .map_err(|_| DiamondTokenError::MissingMintExtension)?;
+
+        let token_state_seeds = &[TOKEN_STATE_SEED, &[ctx.accounts.token_state.bump]];
+        let signer = &[&token_state_seeds[..]];
+
+        // New confidential accounts always need approve_confidential_account,
+        // so blacklisted or unverified owners cannot opt in
+        let ix = confidential_transfer::instruction::update_mint(
+            ctx.accounts.token_program.key,
+            &mint.key(),
+            &ctx.accounts.token_state.key(),
+            &[],
+            false,
+            auditor_elgamal_pubkey.map(PodElGamalPubkey::from),
+        )?;
+        invoke_signed(
+            &ix,
+            &[mint, ctx.accounts.token_state.to_account_info()],
+            signer,
+        )?;
+
+        emit!(ConfidentialAuditorUpdated {
+            auditor_elgamal_pubkey,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Approval is a governance decision: once approved, balances move out of
+    /// sight, so a later blacklisting can only freeze the account.
+    pub fn approve_confidential_account(ctx: Context<ApproveConfidentialAccount>) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        let current_time = Clock::get()?.unix_timestamp;
+        compliance::require_not_blacklisted(
+            &ctx.accounts.owner_blacklist_entry,
+            current_time,
+            DiamondTokenError::AddressBlacklisted,
+        )?;
+        compliance::require_kyc_verified(
+            &ctx.accounts.config,
+            &ctx.accounts.owner_kyc_record,
+            current_time,
+        )?;
+
+        let token_state_seeds = &[TOKEN_STATE_SEED, &[ctx.accounts.token_state.bump]];
+        let signer = &[&token_state_seeds[..]];
+
+        let ix = confidential_transfer::instruction::approve_account(
+            ctx.accounts.token_program.key,
+            &ctx.accounts.token_account.key(),
+            &ctx.accounts.mint.key(),
+            &ctx.accounts.token_state.key(),
+            &[],
+        )?;
+        invoke_signed(
+            &ix,
+            &[
+                ctx.accounts.token_account.to_account_info(),
+                ctx.accounts.mint.to_account_info(),
+                ctx.accounts.token_state.to_account_info(),
+            ],
+            signer,
+        )?;
+
+        emit!(ConfidentialAccountApproved {
+            account: ctx.accounts.token_account.key(),
+            owner: ctx.accounts.token_account.owner,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn propose_interest_rate(ctx: Context<ManageInterestRate>, rate_bps: i16) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        require!(
+            (0..=MAX_INTEREST_RATE_BPS).contains(&rate_bps),
+            DiamondTokenError::InterestRateOutOfBounds
+        );
+
+        let mint = ctx.accounts.mint.to_account_info();
+        extensions::require_token_2022(&mint)?;
+        let interest_config =
+            token_interface::get_mint_extension_data::<InterestBearingConfig>(&mint)
+                .map_err(|_| DiamondTokenError::MissingMintExtension)?;
+
+        // A rate that apply_interest_rate could never sign for would only
+        // give holders notice of a change that cannot happen
+        require!(
+            Option::<Pubkey>::from(interest_config.rate_authority)
+                == Some(ctx.accounts.token_state.key()),
+            DiamondTokenError::InvalidRateAuthority
+        );
+
+        // Holders get the timelock as notice before the rate changes;
+        // a new proposal replaces any pending one and restarts it
+        let executable_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(INTEREST_RATE_TIMELOCK)
+            .ok_or(DiamondTokenError::MathOverflow)?;
+        ctx.accounts.config.pending_interest_rate = Some(PendingRate {
+            rate_bps,
+            executable_at,
+        });
+
+        emit!(InterestRateProposed {
+            rate_bps,
+            executable_at,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_interest_rate(ctx: Context<ManageInterestRate>) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        let pending = ctx
+            .accounts
+            .config
+            .pending_interest_rate
+            .take()
+            .ok_or(DiamondTokenError::NoPendingInterestRate)?;
+
+        emit!(InterestRateCancelled {
+            rate_bps: pending.rate_bps,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn apply_interest_rate(ctx: Context<ApplyInterestRate>) -> Result<()> {
+        let pending = ctx
+            .accounts
+            .config
+            .pending_interest_rate
+            .ok_or(DiamondTokenError::NoPendingInterestRate)?;
+
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            current_time >= pending.executable_at,
+            DiamondTokenError::InterestRateTimelocked
+        );
+
+        let token_state_seeds = &[TOKEN_STATE_SEED, &[ctx.accounts.token_state.bump]];
+        let signer = &[&token_state_seeds[..]];
+
+        token_interface::interest_bearing_mint_update_rate(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::InterestBearingMintUpdateRate {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    rate_authority: ctx.accounts.token_state.to_account_info(),
+                },
+                signer,
+            ),
+            pending.rate_bps,
+        )?;
+
+        ctx.accounts.config.pending_interest_rate = None;
+
+        emit!(InterestRateApplied {
+            rate_bps: pending.rate_bps,
+            cranker: ctx.accounts.cranker.key(),
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn initialize_metadata(
+        ctx: Context<UpdateMetadata>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        let mint = ctx.accounts.mint.to_account_info();
+        extensions::require_token_2022(&mint)?;
+
+        let token_state_seeds = &[TOKEN_STATE_SEED, &[ctx.accounts.token_state.bump]];
+        let signer = &[&token_state_seeds[..]];
+
+        // The pointer extension is set at mint creation; aim it at the mint
+        // itself so the metadata lives in the mint account
+        let pointer = token_interface::get_mint_extension_data::<MetadataPointer>(&mint)
+            .map_err(|_| DiamondTokenError::MissingMintExtension)?;
+        if Option::<Pubkey>::from(pointer.metadata_address) != Some(mint.key()) {
+            let ix = metadata_pointer::instruction::update(
+                ctx.accounts.token_program.key,
+                &mint.key(),
+                &ctx.accounts.token_state.key(),
+                &[],
+                Some(mint.key()),
+            )?;
+            invoke_signed(
+                &ix,
+                &[mint.clone(), ctx.accounts.token_state.to_account_info()],
+                signer,
+            )?;
+        }
+
+        token_interface::token_metadata_initialize(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::TokenMetadataInitialize {
+                    program_id: ctx.accounts.token_program.to_account_info(),
+                    metadata: mint.clone(),
+                    update_authority: ctx.accounts.token_state.to_account_info(),
+                    mint_authority: ctx.accounts.token_state.to_account_info(),
+                    mint: mint.clone(),
+                },
+                signer,
+            ),
+            name.clone(),
+            symbol.clone(),
+            uri.clone(),
+        )?;
+
+        extensions::top_up_rent(
+            &mint,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+
+        emit!(MetadataInitialized {
+            mint: mint.key(),
+            name,
+            symbol,
+            uri,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn update_metadata_field(
+        ctx: Context<UpdateMetadata>,
+        field: MetadataField,
+        value: String,
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        let mint = ctx.accounts.mint.to_account_info();
+        extensions::require_token_2022(&mint)?;
+
+        let token_state_seeds = &[TOKEN_STATE_SEED, &[ctx.accounts.token_state.bump]];
+        let signer = &[&token_state_seeds[..]];
+
+        token_interface::token_metadata_update_field(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::TokenMetadataUpdateField {
+                    program_id: ctx.accounts.token_program.to_account_info(),
+                    metadata: mint.clone(),
+                    update_authority: ctx.accounts.token_state.to_account_info(),
+                },
+                signer,
+            ),
+            field.clone().into(),
+            value.clone(),
+        )?;
+
+        extensions::top_up_rent(
+            &mint,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+
+        emit!(MetadataUpdated {
+            mint: mint.key(),
+            field,
+            value,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn freeze_account(ctx: Context<FreezeOrThaw>) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        // Recorded even if the account is already frozen for another reason,
+        // so that lifting that reason does not thaw it
+        let record = &mut ctx.accounts.freeze_record;
+        require!(!record.governance, DiamondTokenError::AccountAlreadyFrozen);
+        record.token_account = ctx.accounts.token_account.key();
+        record.governance = true;
+        record.bump = ctx.bumps.freeze_record;
+
+        if !ctx.accounts.token_account.is_frozen() {
+            let token_state_seeds = &[TOKEN_STATE_SEED, &[ctx.accounts.token_state.bump]];
+            let signer = &[&token_state_seeds[..]];
+
+            compliance::set_frozen(
+                &ctx.accounts.token_account.to_account_info(),
+                true,
+                &ctx.accounts.mint.to_account_info(),
+                &ctx.accounts.token_state.to_account_info(),
+                &ctx.accounts.token_program.to_account_info(),
+                signer,
+            )?;
+        }
+
+        emit!(AccountFrozen {
+            account: ctx.accounts.token_account.key(),
+            owner: ctx.accounts.token_account.owner,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn thaw_account(ctx: Context<FreezeOrThaw>) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        require!(
+            ctx.accounts.token_account.is_frozen(),
+            DiamondTokenError::AccountNotFrozen
+        );
+
+        // Lifts the governance freeze only; a blacklisting is lifted by removal
+        let record = &mut ctx.accounts.freeze_record;
+        record.token_account = ctx.accounts.token_account.key();
+        record.governance = false;
+        record.bump = ctx.bumps.freeze_record;
+        require!(!record.is_held(), DiamondTokenError::AccountHeldFrozen);
+
+        let token_state_seeds = &[TOKEN_STATE_SEED, &[ctx.accounts.token_state.bump]];
+        let signer = &[&token_state_seeds[..]];
+
+        compliance::set_frozen(
+            &ctx.accounts.token_account.to_account_info(),
+            false,
+            &ctx.accounts.mint.to_account_info(),
+            &ctx.accounts.token_state.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            signer,
+        )?;
+
+        emit!(AccountThawed {
+            account: ctx.accounts.token_account.key(),
+            owner: ctx.accounts.token_account.owner,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn migrate_blacklist<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigrateBlacklist<'info>>,
+        reason: BlacklistReason,
+        case_reference: [u8; 32],
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        // remaining_accounts are the entry PDAs for the first addresses in the legacy list
+        let count = ctx.remaining_accounts.len();
+        require!(
+            count > 0
+                && count <= MAX_BATCH_BLACKLIST_ACCOUNTS
+                && count <= ctx.accounts.blacklist.addresses.len(),
+            DiamondTokenError::InvalidBatchSize
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+
+        for (entry, address) in ctx
+            .remaining_accounts
+            .iter()
+            .zip(ctx.accounts.blacklist.addresses.iter())
+        {
+            let bump = blacklist::verify_entry_address(entry, address)?;
+
+            // Already added through add_to_blacklist since the upgrade
+            if blacklist::entry_exists(entry) {
+                continue;
+            }
+
+            // Legacy entries carry no metadata, so the batch shares one reason
+            let record = BlacklistEntry {
+                address: *address,
+                reason,
+                expires_at: None,
+                added_by: ctx.accounts.authority.key(),
+                added_at: current_time,
+                case_reference,
+                bump,
+            };
+
+            blacklist::create_entry(
+                entry,
+                record,
+                &ctx.accounts.authority.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+            )?;
+
+            emit!(BlacklistUpdated {
+                address: *address,
+                is_blacklisted: true,
+                reason,
+                expires_at: None,
+                added_by: ctx.accounts.authority.key(),
+                added_at: current_time,
+                case_reference,
+                authority: ctx.accounts.authority.key(),
+            });
+        }
+
+        let legacy = &mut ctx.accounts.blacklist;
+        legacy.addresses.drain(..count);
+        let remaining = legacy.addresses.len();
+
+        emit!(BlacklistMigrated {
+            migrated: count as u32,
+            remaining: remaining as u32,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        // Reclaim the legacy account once everything is migrated
+        if remaining == 0 {
+            legacy.close(ctx.accounts.authority.to_account_info())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn batch_add_to_blacklist<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchUpdateBlacklist<'info>>,
+        addresses: Vec<Pubkey>,
+        reason: BlacklistReason,
+        expires_at: Option<i64>,
+        case_reference: [u8; 32],
+        mode: DuplicateMode,
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        // remaining_accounts are the entry PDAs, in the same order as addresses,
+        // followed by (token account, freeze record) pairs for auto-freeze
+        require!(
+            !addresses.is_empty()
+                && addresses.len() <= MAX_BATCH_BLACKLIST_ACCOUNTS
+                && addresses.len() <= ctx.remaining_accounts.len(),
+            DiamondTokenError::InvalidBatchSize
+        );
+        let (entries, token_accounts) = ctx.remaining_accounts.split_at(addresses.len());
+
+        let current_time = Clock::get()?.unix_timestamp;
+        if let Some(expires_at) = expires_at {
+            require!(expires_at > current_time, DiamondTokenError::InvalidExpiry);
+        }
+
+        for (entry, address) in entries.iter().zip(addresses.iter()) {
+            let bump = blacklist::verify_entry_address(entry, address)?;
+
+            if blacklist::is_blacklisted(entry, current_time)? {
+                require!(
+                    mode == DuplicateMode::Skip,
+                    DiamondTokenError::AddressAlreadyBlacklisted
+                );
+                continue;
+            }
+
+            let record = BlacklistEntry {
+                address: *address,
+                reason,
+                expires_at,
+                added_by: ctx.accounts.authority.key(),
+                added_at: current_time,
+                case_reference,
+                bump,
+            };
+
+            if blacklist::entry_exists(entry) {
+                // Expired entries are refreshed in place
+                blacklist::write_entry(entry, &record)?;
+            } else {
+                blacklist::create_entry(
+                    entry,
+                    record,
+                    &ctx.accounts.authority.to_account_info(),
+                    &ctx.accounts.system_program.to_account_info(),
+                )?;
+            }
+
+            emit!(BlacklistUpdated {
+                address: *address,
+                is_blacklisted: true,
+                reason,
+                expires_at,
+                added_by: ctx.accounts.authority.key(),
+                added_at: current_time,
+                case_reference,
+                authority: ctx.accounts.authority.key(),
+            });
+        }
+
+        // Freeze the listed wallets' DIAMOND accounts, as add_to_blacklist does
+        if ctx.accounts.config.auto_freeze_on_blacklist {
+            let token_state_seeds = &[TOKEN_STATE_SEED, &[ctx.accounts.token_state.bump]];
+            let signer = &[&token_state_seeds[..]];
+
+            compliance::freeze_blacklisted_accounts(
+                token_accounts,
+                &addresses,
+                &ctx.accounts.mint.to_account_info(),
+                &ctx.accounts.token_state.to_account_info(),
+                &ctx.accounts.token_program.to_account_info(),
+                &ctx.accounts.authority.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                signer,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn batch_remove_from_blacklist<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchUpdateBlacklist<'info>>,
+        addresses: Vec<Pubkey>,
+        mode: DuplicateMode,
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        // remaining_accounts are the entry PDAs, in the same order as addresses,
+        // followed by (token account, freeze record) pairs for auto-freeze
+        require!(
+            !addresses.is_empty()
+                && addresses.len() <= MAX_BATCH_BLACKLIST_ACCOUNTS
+                && addresses.len() <= ctx.remaining_accounts.len(),
+            DiamondTokenError::InvalidBatchSize
+        );
+        let (entries, token_accounts) = ctx.remaining_accounts.split_at(addresses.len());
+
+        for (entry, address) in entries.iter().zip(addresses.iter()) {
+            blacklist::verify_entry_address(entry, address)?;
+
+            if !blacklist::entry_exists(entry) {
+                require!(
+                    mode == DuplicateMode::Skip,
+                    DiamondTokenError::AddressNotBlacklisted
+                );
+                continue;
+            }
+
+            let record = {
+                let data = entry.try_borrow_data()?;
+                BlacklistEntry::try_deserialize(&mut &data[..])?
+            };
+
+            blacklist::close_entry(entry, &ctx.accounts.authority.to_account_info())?;
+
+            emit!(BlacklistUpdated {
+                address: *address,
+                is_blacklisted: false,
+                reason: record.reason,
+                expires_at: record.expires_at,
+                added_by: record.added_by,
+                added_at: record.added_at,
+                case_reference: record.case_reference,
+                authority: ctx.accounts.authority.key(),
+            });
+        }
+
+        // Thaw what auto-freeze froze, as remove_from_blacklist does
+        if ctx.accounts.config.auto_freeze_on_blacklist {
+            let token_state_seeds = &[TOKEN_STATE_SEED, &[ctx.accounts.token_state.bump]];
+            let signer = &[&token_state_seeds[..]];
+
+            compliance::thaw_blacklisted_accounts(
+                token_accounts,
+                &addresses,
+                &ctx.accounts.mint.to_account_info(),
+                &ctx.accounts.token_state.to_account_info(),
+                &ctx.accounts.token_program.to_account_info(),
+                signer,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn initialize_seizure_escrow(ctx: Context<InitializeSeizureEscrow>) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        Ok(())
+    }
+
+    /// Remaining accounts are the transfer-hook accounts for the
+    /// permanent-delegate transfer, when the mint has a hook.
+    pub fn seize<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Seize<'info>>,
+        case_reference: [u8; 32],
+        amount: u64,
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        require!(amount > 0, DiamondTokenError::InvalidAmount);
+        require!(
+            ctx.accounts.target_account.amount >= amount,
+            DiamondTokenError::InsufficientFunds
+        );
+
+        // Only holders under an active blacklist entry can be seized from
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            blacklist::is_blacklisted(&ctx.accounts.target_owner_blacklist_entry, current_time)?,
+            DiamondTokenError::TargetNotBlacklisted
+        );
+
+        let token_state_seeds = &[TOKEN_STATE_SEED, &[ctx.accounts.token_state.bump]];
+        let signer = &[&token_state_seeds[..]];
+
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let mint = ctx.accounts.mint.to_account_info();
+        let target = ctx.accounts.target_account.to_account_info();
+        let escrow = ctx.accounts.seizure_escrow.to_account_info();
+        let token_state_info = ctx.accounts.token_state.to_account_info();
+        let was_frozen = ctx.accounts.target_account.is_frozen();
+
+        let method = if compliance::has_permanent_delegate(&mint, &ctx.accounts.token_state.key()) {
+            // Frozen accounts cannot send, even to the permanent delegate
+            if was_frozen {
+                token_interface::thaw_account(CpiContext::new_with_signer(
+                    token_program.clone(),
+                    token_interface::ThawAccount {
+                        account: target.clone(),
+                        mint: mint.clone(),
+                        authority: token_state_info.clone(),
+                    },
+                    signer,
+                ))?;
+            }
+
+            invoke_transfer_checked(
+                token_program.key,
+                target.clone(),
+                mint.clone(),
+                escrow.clone(),
+                token_state_info.clone(),
+                ctx.remaining_accounts,
+                amount,
+                ctx.accounts.mint.decimals,
+                signer,
+            )?;
+
+            if was_frozen {
+                token_interface::freeze_account(CpiContext::new_with_signer(
+                    token_program.clone(),
+                    token_interface::FreezeAccount {
+                        account: target.clone(),
+                        mint: mint.clone(),
+                        authority: token_state_info.clone(),
+                    },
+                    signer,
+                ))?;
+            }
+
+            SeizureMethod::PermanentDelegate
+        } else {
+            // Classic SPL gives the program no authority over holder balances,
+            // so lock them in place and re-mint the same amount into escrow.
+            // Balance already locked by an earlier seizure cannot be re-minted.
+            let record = ctx
+                .accounts
+                .freeze_record
+                .as_mut()
+                .ok_or(DiamondTokenError::FreezeRecordRequired)?;
+            let seized_amount = record
+                .seized_amount
+                .checked_add(amount)
+                .ok_or(DiamondTokenError::MathOverflow)?;
+            require!(
+                seized_amount <= ctx.accounts.target_account.amount,
+                DiamondTokenError::InsufficientFunds
+            );
+            record.token_account = ctx.accounts.target_account.key();
+            record.seized_amount = seized_amount;
+            record.bump = ctx
+                .bumps
+                .freeze_record
+                .ok_or(DiamondTokenError::FreezeRecordRequired)?;
+
+            if !was_frozen {
+                token_interface::freeze_account(CpiContext::new_with_signer(
+                    token_program.clone(),
+                    token_interface::FreezeAccount {
+                        account: target.clone(),
+                        mint: mint.clone(),
+                        authority: token_state_info.clone(),
+                    },
+                    signer,
+                ))?;
+            }
+
+            token_interface::mint_to(
+                CpiContext::new_with_signer(
+                    token_program.clone(),
+                    token_interface::MintTo {
+                        mint: mint.clone(),
+                        to: escrow.clone(),
+                        authority: token_state_info.clone(),
+                    },
+                    signer,
+                ),
+                amount,
+            )?;
+
+            // The locked original no longer counts, so the cap is unaffected
+            let token_state = &mut ctx.accounts.token_state;
+            token_state.total_supply = token_state
+                .total_supply
+                .checked_add(amount)
+                .ok_or(DiamondTokenError::MathOverflow)?;
+            token_state.seized_locked = token_state
+                .seized_locked
+                .checked_add(amount)
+                .ok_or(DiamondTokenError::MathOverflow)?;
+
+            SeizureMethod::FreezeAndRemint
+        };
+
+        let owner = ctx.accounts.target_account.owner;
+        ctx.accounts.seizure_record.set_inner(SeizureRecord {
+            case_reference,
+            target_account: ctx.accounts.target_account.key(),
+            owner,
+            amount,
+            method,
+            authority: ctx.accounts.authority.key(),
+            timestamp: current_time,
+            bump: ctx.bumps.seizure_record,
+        });
+
+        emit!(TokensSeized {
+            case_reference,
+            target_account: ctx.accounts.target_account.key(),
+            owner,
+            amount,
+            escrow: ctx.accounts.seizure_escrow.key(),
+            method,
+            new_total_supply: ctx.accounts.token_state.total_supply,
+            authority: ctx.accounts.authority.key(),
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    /// Remaining accounts are the transfer-hook accounts for both the vault
+    /// and the fee transfer, when the mint has a hook.
+    pub fn purchase_item<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PurchaseItem<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        // Check if token operations are paused
+        require!(
+            !ctx.accounts.token_state.is_paused,
+            DiamondTokenError::Paused
+        );
+
+        // Check blacklist
+        require!(
+            ctx.accounts.user_token_account.owner == ctx.accounts.user.key(),
+            DiamondTokenError::InvalidTokenAccount
+        );
+        let current_time = Clock::get()?.unix_timestamp;
+        compliance::require_not_blacklisted(
+            &ctx.accounts.user_blacklist_entry,
+            current_time,
+            DiamondTokenError::AddressBlacklisted,
+        )?;
+        compliance::require_kyc_verified(
+            &ctx.accounts.config,
+            &ctx.accounts.user_kyc_record,
+            current_time,
+        )?;
+
+        // Check minimum purchase amount
+        require!(
+            amount >= MIN_PURCHASE_AMOUNT,
+            DiamondTokenError::PurchaseAmountTooSmall
+        );
+
+        // Processing fee is charged on top of the purchase amount
+        let fee_amount = Config::bps_of(amount, ctx.accounts.config.purchase_fee_bps)?;
+        let total_amount = amount
+            .checked_add(fee_amount)
+            .ok_or(DiamondTokenError::MathOverflow)?;
+
+        // Check if user has sufficient balance
+        require!(
+            ctx.accounts.user_token_account.amount >= total_amount,
+            DiamondTokenError::InsufficientBalance
+        );
+
+        // Transfer tokens to vault
+        invoke_transfer_checked(
+            ctx.accounts.token_program.key,
+            ctx.accounts.user_token_account.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.user.to_account_info(),
+            ctx.remaining_accounts,
+            amount,
+            ctx.accounts.mint.decimals,
+            &[],
+        )?;
+
+        // Transfer fee to the fee treasury
+        if fee_amount > 0 {
+            invoke_transfer_checked(
+                ctx.accounts.token_program.key,
+                ctx.accounts.user_token_account.to_account_info(),
+                ctx.accounts.mint.to_account_info(),
+                ctx.accounts.fee_account.to_account_info(),
+                ctx.accounts.user.to_account_info(),
+                ctx.remaining_accounts,
+                fee_amount,
+                ctx.accounts.mint.decimals,
+                &[],
+            )?;
+        }
+
+        // Emit event with more details
+        emit!(ItemPurchased {
+            buyer: ctx.accounts.user.key(),
+            amount,
+            fee_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+            vault_balance: ctx.accounts.vault.amount,
+        });
+
+        Ok(())
+    }
+
+    pub fn verify_reserve<'info>(
+        ctx: Context<'_, '_, 'info, 'info, VerifyReserve<'info>>,
+    ) -> Result<()> {
+        // Reserve vaults are passed as remaining accounts, in config order
+        let attestation = reserve::attest(
+            &ctx.accounts.token_state,
+            &[
+                Some(&ctx.accounts.vault),
+                ctx.accounts.seizure_escrow.as_ref(),
+                ctx.accounts.buyback_account.as_ref(),
+                ctx.accounts.fee_account.as_ref(),
+            ],
+            &ctx.accounts.config,
+            ctx.remaining_accounts,
+            ctx.accounts.sol_price_feed.as_deref(),
+        )?;
+
+        // Emit verification event
+        emit!(ReserveVerified {
+            total_supply: attestation.total_supply,
+            circulating_supply: attestation.circulating_supply,
+            liability_usd: attestation.liability_usd,
+            reserve_usd: attestation.reserve_usd,
+            reserve_ratio_bps: attestation.reserve_ratio_bps,
+            assets: attestation.assets,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn initialize_reserve_history(ctx: Context<InitializeReserveHistory>) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        let reserve_history = &mut ctx.accounts.reserve_history;
+        reserve_history.snapshots = Vec::new();
+        reserve_history.next_index = 0;
+        reserve_history.last_recorded_at = 0;
+        reserve_history.bump = ctx.bumps.reserve_history;
+
+        Ok(())
+    }
+
+    /// Permissionless crank that stores a reserve attestation on chain, at most
+    /// once per `MIN_RESERVE_ATTESTATION_INTERVAL`.
+    pub fn record_reserve<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RecordReserve<'info>>,
+    ) -> Result<()> {
+        // Reserve vaults are passed as remaining accounts, in config order
+        let attestation = reserve::attest(
+            &ctx.accounts.token_state,
+            &[
+                Some(&ctx.accounts.vault),
+                ctx.accounts.seizure_escrow.as_ref(),
+                ctx.accounts.buyback_account.as_ref(),
+                ctx.accounts.fee_account.as_ref(),
+            ],
+            &ctx.accounts.config,
+            ctx.remaining_accounts,
+            ctx.accounts.sol_price_feed.as_deref(),
+        )?;
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let caller = ctx.accounts.caller.key();
+        let reserve_ratio_bps = attestation.reserve_ratio_bps;
+
+        let reserve_history = &mut ctx.accounts.reserve_history;
+        let index = reserve_history.next_index;
+        reserve_history.record(attestation.into_snapshot(caller, current_time))?;
+
+        emit!(ReserveRecorded {
+            index,
+            reserve_ratio_bps,
+            caller,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_reserve_vaults<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetReserveVaults<'info>>,
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        require!(
+            ctx.remaining_accounts.len() <= MAX_RESERVE_VAULTS,
+            DiamondTokenError::InvalidBatchSize
+        );
+
+        let token_state = ctx.accounts.token_state.key();
+        let mut vaults = Vec::with_capacity(ctx.remaining_accounts.len());
+        for vault in ctx.remaining_accounts {
+            reserve::load_vault(vault, &token_state)?;
+            require!(
+                !vaults.contains(vault.key),
+                DiamondTokenError::InvalidReserveAccount
+            );
+            vaults.push(vault.key());
+        }
+
+        ctx.accounts.config.reserve_vaults = vaults.clone();
+
+        emit!(ReserveVaultsUpdated {
+            vaults,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        let config = &mut ctx.accounts.config;
+        config.swap_program = Pubkey::default();
+        config.usdt_reserve = Pubkey::default();
+        config.buy_fee_bps = 0;
+        config.redeem_fee_bps = 0;
+        config.purchase_fee_bps = 0;
+        config.volume_tiers = Vec::new();
+        config.auto_freeze_on_blacklist = false;
+        config.compliance_mode = ComplianceMode::DenyList;
+        config.kyc_attester = Pubkey::default();
+        config.kyc_tier_limits = [TierLimits::UNLIMITED; KYC_TIER_COUNT];
+        config.compliance_signer = Pubkey::default();
+        config.require_geo_attestation = false;
+        config.denied_jurisdictions = Vec::new();
+        config.pending_interest_rate = None;
+        config.reserve_vaults = Vec::new();
+        config.kyc_exempt_owners = Vec::new();
+        config.bump = ctx.bumps.config;
+
+        Ok(())
+    }
+
+    pub fn set_buyback_config(ctx: Context<SetBuybackConfig>, swap_program: Pubkey) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        let config = &mut ctx.accounts.config;
+        config.swap_program = swap_program;
+        config.usdt_reserve = ctx.accounts.usdt_reserve.key();
+
+        emit!(BuybackConfigUpdated {
+            swap_program,
+            usdt_reserve: config.usdt_reserve,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    /// There is no redeem instruction yet; `redeem_fee_bps` is the rate it
+    /// will charge, set and published ahead of it.
+    pub fn set_fees(
+        ctx: Context<SetFees>,
+        buy_fee_bps: u16,
+        redeem_fee_bps: u16,
+        purchase_fee_bps: u16,
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        require!(
+            buy_fee_bps <= MAX_BUY_FEE_BPS
+                && redeem_fee_bps <= MAX_REDEEM_FEE_BPS
+                && purchase_fee_bps <= MAX_PURCHASE_FEE_BPS,
+            DiamondTokenError::FeeTooHigh
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.buy_fee_bps = buy_fee_bps;
+        config.redeem_fee_bps = redeem_fee_bps;
+        config.purchase_fee_bps = purchase_fee_bps;
+
+        emit!(FeesUpdated {
+            buy_fee_bps,
+            redeem_fee_bps,
+            purchase_fee_bps,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn set_volume_tiers(ctx: Context<SetVolumeTiers>, tiers: Vec<VolumeTier>) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        Config::validate_volume_tiers(&tiers)?;
+
+        ctx.accounts.config.volume_tiers = tiers.clone();
+
+        emit!(VolumeTiersUpdated {
+            tiers,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn set_geo_attestation(
+        ctx: Context<SetGeoAttestation>,
+        compliance_signer: Pubkey,
+        required: bool,
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        require!(
+            !required || compliance_signer != Pubkey::default(),
+            DiamondTokenError::InvalidGeoAttestation
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.compliance_signer = compliance_signer;
+        config.require_geo_attestation = required;
+
+        emit!(GeoAttestationConfigUpdated {
+            compliance_signer,
+            required,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn set_denied_jurisdictions(
+        ctx: Context<SetDeniedJurisdictions>,
+        jurisdictions: Vec<[u8; 2]>,
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        require!(
+            jurisdictions.len() <= MAX_DENIED_JURISDICTIONS,
+            DiamondTokenError::TooManyJurisdictions
+        );
+
+        ctx.accounts.config.denied_jurisdictions = jurisdictions.clone();
+
+        emit!(DeniedJurisdictionsUpdated {
+            jurisdictions,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn quote_mint(ctx: Context<QuoteMint>, amount: u64) -> Result<MintQuote> {
+        pricing::quote_mint(
+            amount,
+            &ctx.accounts.payment_token,
+            ctx.accounts.sol_price_feed.as_deref(),
+            &ctx.accounts.config,
+        )
+    }
+
+    pub fn set_transfer_fee(
+        ctx: Context<ManageTransferFee>,
+        basis_points: u16,
+        maximum_fee: u64,
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        require!(
+            basis_points <= MAX_TRANSFER_FEE_BPS,
+            DiamondTokenError::FeeTooHigh
+        );
+
+        let mint = ctx.accounts.mint.to_account_info();
+        extensions::require_token_2022(&mint)?;
+
+        let token_state_seeds = &[TOKEN_STATE_SEED, &[ctx.accounts.token_state.bump]];
+        let signer = &[&token_state_seeds[..]];
+
+        // Token-2022 applies the new fee two epochs from now
+        token_interface::transfer_fee_set(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::TransferFeeSetTransferFee {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint,
+                    authority: ctx.accounts.token_state.to_account_info(),
+                },
+                signer,
+            ),
+            basis_points,
+            maximum_fee,
+        )?;
+
+        emit!(TransferFeeUpdated {
+            basis_points,
+            maximum_fee,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn harvest_transfer_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ManageTransferFee<'info>>,
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        // Holder accounts to sweep, bounded to stay within compute limits
+        require!(
+            !ctx.remaining_accounts.is_empty()
+                && ctx.remaining_accounts.len() <= MAX_HARVEST_ACCOUNTS,
+            DiamondTokenError::InvalidBatchSize
+        );
+
+        let mint = ctx.accounts.mint.to_account_info();
+        extensions::require_token_2022(&mint)?;
+        let withheld_before = extensions::mint_withheld_fees(&mint)?;
+
+        token_interface::harvest_withheld_tokens_to_mint(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::HarvestWithheldTokensToMint {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: mint.clone(),
+                },
+            ),
+            ctx.remaining_accounts.to_vec(),
+        )?;
+
+        let amount = extensions::mint_withheld_fees(&mint)?
+            .checked_sub(withheld_before)
+            .ok_or(DiamondTokenError::MathOverflow)?;
+
+        emit!(TransferFeesHarvested {
+            accounts: ctx.remaining_accounts.len() as u32,
+            amount,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn withdraw_transfer_fees(ctx: Context<WithdrawTransferFees>) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        let mint = ctx.accounts.mint.to_account_info();
+        extensions::require_token_2022(&mint)?;
+
+        let amount = extensions::mint_withheld_fees(&mint)?;
+        require!(amount > 0, DiamondTokenError::InvalidAmount);
+
+        let token_state_seeds = &[TOKEN_STATE_SEED, &[ctx.accounts.token_state.bump]];
+        let signer = &[&token_state_seeds[..]];
+
+        token_interface::withdraw_withheld_tokens_from_mint(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::WithdrawWithheldTokensFromMint {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                mint,
+                destination: ctx.accounts.fee_account.to_account_info(),
+                authority: ctx.accounts.token_state.to_account_info(),
+            },
+            signer,
+        ))?;
+
+        emit!(TransferFeesWithdrawn {
+            destination: ctx.accounts.fee_account.key(),
+            amount,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Remaining accounts are the transfer-hook accounts, when `mint` has a hook.
+    pub fn withdraw_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawFees<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        require!(amount > 0, DiamondTokenError::InvalidAmount);
+        require!(
+            ctx.accounts.fee_account.amount >= amount,
+            DiamondTokenError::InsufficientFunds
+        );
+
+        let fee_treasury_seeds = &[FEE_TREASURY_SEED, &[ctx.bumps.fee_treasury]];
+        let signer = &[&fee_treasury_seeds[..]];
+
+        invoke_transfer_checked(
+            ctx.accounts.token_program.key,
+            ctx.accounts.fee_account.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.destination.to_account_info(),
+            ctx.accounts.fee_treasury.to_account_info(),
+            ctx.remaining_accounts,
+            amount,
+            ctx.accounts.mint.decimals,
+            signer,
+        )?;
+
+        emit!(FeesWithdrawn {
+            fee_account: ctx.accounts.fee_account.key(),
+            destination: ctx.accounts.destination.key(),
+            amount,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Remaining accounts are the reserve vaults in config order, as for
+    /// `verify_reserve`, followed by the swap program's accounts.
+    pub fn buyback_and_burn<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BuybackAndBurn<'info>>,
+        usdt_amount: u64,
+        minimum_diamond_out: u64,
+    ) -> Result<()> {
+        // Verify admin signature
+        ctx.accounts
+            .token_state
+            .require_admin(&ctx.accounts.authority.key())?;
+
+        require!(usdt_amount > 0, DiamondTokenError::InvalidAmount);
+
+        require!(
+            ctx.remaining_accounts.len() >= ctx.accounts.config.reserve_vaults.len(),
+            DiamondTokenError::InvalidReserveAccount
+        );
+        let (reserve_vaults, swap_accounts) = ctx
+            .remaining_accounts
+            .split_at(ctx.accounts.config.reserve_vaults.len());
+
+        // Only the reserve surplus above the attested liability can be spent
+        let surplus_before = reserve::attest(
+            &ctx.accounts.token_state,
+            &[
+                Some(&ctx.accounts.vault),
+                ctx.accounts.seizure_escrow.as_ref(),
+                Some(&ctx.accounts.buyback_account),
+                ctx.accounts.fee_account.as_ref(),
+            ],
+            &ctx.accounts.config,
+            reserve_vaults,
+            ctx.accounts.sol_price_feed.as_deref(),
+        )?
+        .surplus_usd();
+        let reserve_before = ctx.accounts.usdt_reserve.amount;
+
+        require!(
+            usdt_amount <= surplus_before,
+            DiamondTokenError::InsufficientReserveSurplus
+        );
+
+        let diamond_before = ctx.accounts.buyback_account.amount;
+
+        let token_state_seeds = &[TOKEN_STATE_SEED, &[ctx.accounts.token_state.bump]];
+        let signer = &[&token_state_seeds[..]];
+
+        // The swap program never sees a token_state signature: it gets a
+        // dedicated PDA allowed to move exactly `usdt_amount` of the reserve
+        token_interface::approve(
+            CpiContext::new_with_signer(
+                ctx.accounts.reserve_token_program.to_account_info(),
+                token_interface::Approve {
+                    to: ctx.accounts.usdt_reserve.to_account_info(),
+                    delegate: ctx.accounts.buyback_authority.to_account_info(),
+                    authority: ctx.accounts.token_state.to_account_info(),
+                },
+                signer,
+            ),
+            usdt_amount,
+        )?;
+
+        let buyback_authority_seeds = &[BUYBACK_AUTHORITY_SEED, &[ctx.bumps.buyback_authority]];
+
+        // Pay USDT to the seller and receive DIAMOND through the swap program
+        swap::swap(
+            &ctx.accounts.swap_program.to_account_info(),
+            &ctx.accounts.usdt_reserve.to_account_info(),
+            &ctx.accounts.buyback_account.to_account_info(),
+            &ctx.accounts.buyback_authority.to_account_info(),
+            &ctx.accounts.reserve_token_program.to_account_info(),
+            swap_accounts,
+            usdt_amount,
+            minimum_diamond_out,
+            &[&buyback_authority_seeds[..]],
+        )?;
+
+        // Drop whatever allowance the swap left unused
+        token_interface::revoke(CpiContext::new_with_signer(
+            ctx.accounts.reserve_token_program.to_account_info(),
+            token_interface::Revoke {
+                source: ctx.accounts.usdt_reserve.to_account_info(),
+                authority: ctx.accounts.token_state.to_account_info(),
+            },
+            signer,
+        ))?;
+
+        ctx.accounts.usdt_reserve.reload()?;
+        ctx.accounts.buyback_account.reload()?;
+
+        let usdt_spent = reserve_before
+            .checked_sub(ctx.accounts.usdt_reserve.amount)
+            .ok_or(DiamondTokenError::MathOverflow)?;
+        let diamond_burned = ctx
+            .accounts
+            .buyback_account
+            .amount
+            .checked_sub(diamond_before)
+            .ok_or(DiamondTokenError::MathOverflow)?;
+        swap::check_swap_amounts(usdt_amount, usdt_spent, diamond_burned, minimum_diamond_out)?;
+
+        // Burn everything that was bought back
+        token_interface::burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::Burn {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    from: ctx.accounts.buyback_account.to_account_info(),
+                    authority: ctx.accounts.token_state.to_account_info(),
+                },
+                signer,
+            ),
+            diamond_burned,
+        )?;
+
+        let token_state = &mut ctx.accounts.token_state;
+        token_state.total_supply = token_state
+            .total_supply
+            .checked_sub(diamond_burned)
+            .ok_or(DiamondTokenError::ArithmeticOverflow)?;
+
+        emit!(BuybackBurned {
+            usdt_spent,
+            diamond_burned,
+            surplus_before,
+            swap_program: ctx.accounts.swap_program.key(),
+            new_total_supply: token_state.total_supply,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = TokenState::LEN,
+        seeds = [TOKEN_STATE_SEED],
+        bump
+    )]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [VAULT_SEED],
+        bump,
+        token::mint = mint,
+        token::authority = token_state,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub rent: Sysvar<'info, Rent>,
+
+    /// CHECK: Multisig account is validated in the instruction
+    pub multisig: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MintByUser<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut)]
+    pub token_state: Account<'info, TokenState>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub payment_token: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub user_payment_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = payment_token,
+        token::authority = token_state,
+        constraint = config.reserve_vaults.contains(&vault.key())
+            @ DiamondTokenError::InvalidReserveAccount
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Blacklist entry PDA for the user, may not exist
+    #[account(seeds = [BLACKLIST_SEED, user.key().as_ref()], bump)]
+    pub user_blacklist_entry: UncheckedAccount<'info>,
+    /// CHECK: KYC record PDA for the user, may not exist
+    #[account(seeds = [KYC_SEED, user.key().as_ref()], bump)]
+    pub user_kyc_record: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserVolume::LEN,
+        seeds = [USER_VOLUME_SEED, user.key().as_ref()],
+        bump
+    )]
+    pub user_volume: Account<'info, UserVolume>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// CHECK: PDA that owns the fee accounts, holds no data
+    #[account(seeds = [FEE_TREASURY_SEED], bump)]
+    pub fee_treasury: UncheckedAccount<'info>,
+    #[account(mut, token::mint = payment_token, token::authority = fee_treasury)]
+    pub fee_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Pyth SOL/USD feed, only required for wrapped SOL payments
+    #[account(address = SOL_USD_PRICE_FEED_PUBKEY @ DiamondTokenError::InvalidPriceFeed)]
+    pub sol_price_feed: Option<UncheckedAccount<'info>>,
+    /// CHECK: Instructions sysvar, read for the geo attestation
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Program of the payment mint, which need not match the DIAMOND mint's
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AdminBurn<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+    /// CHECK: Multisig account is validated in the instruction
+    pub multisig: UncheckedAccount<'info>,
+    #[account(mut, address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, address = token_state.vault @ DiamondTokenError::InvalidTokenAccount)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint, token::authority = token_state)]
+    pub premint_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub refund_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Blacklist entry PDA for the refund owner, may not exist
+    #[account(seeds = [BLACKLIST_SEED, refund_account.owner.as_ref()], bump)]
+    pub refund_owner_blacklist_entry: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct BatchAdminBurn<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(mut, seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(mut, address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    /// CHECK: Authority is validated in the instruction
+    pub authority: UncheckedAccount<'info>,
+    pub token_state: Account<'info, TokenState>,
+    /// CHECK: Multisig account is validated in the instruction
+    pub multisig: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Unpause<'info> {
+    /// CHECK: Authority is validated in the instruction
+    pub authority: UncheckedAccount<'info>,
+    pub token_state: Account<'info, TokenState>,
+    /// CHECK: Multisig account is validated in the instruction
+    pub multisig: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMaxSupply<'info> {
+    /// CHECK: Authority is validated in the instruction
+    pub authority: UncheckedAccount<'info>,
+    pub token_state: Account<'info, TokenState>,
+    /// CHECK: Multisig account is validated in the instruction
+    pub multisig: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeMaxSupplySchedule<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = MaxSupplySchedule::space(MAX_SCHEDULED_REDUCTIONS),
+        seeds = [MAX_SUPPLY_SCHEDULE_SEED],
+        bump
+    )]
+    pub max_supply_schedule: Account<'info, MaxSupplySchedule>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendMaxSupplySchedule<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(mut, seeds = [MAX_SUPPLY_SCHEDULE_SEED], bump = max_supply_schedule.bump)]
+    pub max_supply_schedule: Account<'info, MaxSupplySchedule>,
+}
+
+#[derive(Accounts)]
+pub struct CrankMaxSupplySchedule<'info> {
+    pub cranker: Signer<'info>,
+
+    #[account(mut, seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(mut, seeds = [MAX_SUPPLY_SCHEDULE_SEED], bump = max_supply_schedule.bump)]
+    pub max_supply_schedule: Account<'info, MaxSupplySchedule>,
+}
+
+#[derive(Accounts)]
+#[instruction(address: Pubkey)]
+pub struct AddToBlacklist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = BlacklistEntry::LEN,
+        seeds = [BLACKLIST_SEED, address.as_ref()],
+        bump
+    )]
+    pub blacklist_entry: Account<'info, BlacklistEntry>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(address: Pubkey)]
+pub struct RemoveFromBlacklist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [BLACKLIST_SEED, address.as_ref()],
+        bump = blacklist_entry.bump
+    )]
+    pub blacklist_entry: Account<'info, BlacklistEntry>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetAutoFreeze<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct SetComplianceMode<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct SetKycAttester<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct SetKycExemptOwners<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct IssueKycRecord<'info> {
+    #[account(
+        mut,
+        constraint = attester.key() == config.kyc_attester @ DiamondTokenError::UnauthorizedAttester
+    )]
+    pub attester: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = attester,
+        space = KycRecord::LEN,
+        seeds = [KYC_SEED, wallet.as_ref()],
+        bump
+    )]
+    pub kyc_record: Account<'info, KycRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct UpdateKycRecord<'info> {
+    #[account(
+        constraint = attester.key() == config.kyc_attester @ DiamondTokenError::UnauthorizedAttester
+    )]
+    pub attester: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, seeds = [KYC_SEED, wallet.as_ref()], bump = kyc_record.bump)]
+    pub kyc_record: Account<'info, KycRecord>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct RevokeKycRecord<'info> {
+    #[account(
+        mut,
+        constraint = attester.key() == config.kyc_attester @ DiamondTokenError::UnauthorizedAttester
+    )]
+    pub attester: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = attester,
+        seeds = [KYC_SEED, wallet.as_ref()],
+        bump = kyc_record.bump
+    )]
+    pub kyc_record: Account<'info, KycRecord>,
+}
+
+#[derive(Accounts)]
+pub struct SetKycTierLimits<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeMintAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(mut, address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetDefaultAccountState<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(mut, address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ThawVerifiedAccount<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, token::mint = mint)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: KYC record PDA for the account owner, may not exist
+    #[account(seeds = [KYC_SEED, token_account.owner.as_ref()], bump)]
+    pub owner_kyc_record: UncheckedAccount<'info>,
+
+    /// CHECK: Blacklist entry PDA for the account owner, may not exist
+    #[account(seeds = [BLACKLIST_SEED, token_account.owner.as_ref()], bump)]
+    pub owner_blacklist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: FreezeRecord PDA for the token account, may not exist
+    #[account(seeds = [FREEZE_RECORD_SEED, token_account.key().as_ref()], bump)]
+    pub freeze_record: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetConfidentialTransferAuditor<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(mut, address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveConfidentialAccount<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, token::mint = mint)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: KYC record PDA for the account owner, may not exist
+    #[account(seeds = [KYC_SEED, token_account.owner.as_ref()], bump)]
+    pub owner_kyc_record: UncheckedAccount<'info>,
+
+    /// CHECK: Blacklist entry PDA for the account owner, may not exist
+    #[account(seeds = [BLACKLIST_SEED, token_account.owner.as_ref()], bump)]
+    pub owner_blacklist_entry: UncheckedAccount<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ManageInterestRate<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyInterestRate<'info> {
+    pub cranker: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(mut, address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMetadata<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(mut, address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeOrThaw<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, token::mint = mint)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
-        init,
-        payer = payer,
-        space = Blacklist::space(100), // Maximum 100 blacklisted addresses
-        seeds = [BLACKLIST_SEED],
+        init_if_needed,
+        payer = authority,
+        space = FreezeRecord::LEN,
+        seeds = [FREEZE_RECORD_SEED, token_account.key().as_ref()],
         bump
     )]
+    pub freeze_record: Account<'info, FreezeRecord>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BatchUpdateBlacklist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateBlacklist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(mut, seeds = [BLACKLIST_SEED], bump = blacklist.bump)]
     pub blacklist: Account<'info, Blacklist>,
 
-    /// CHECK: Multisig account is validated in the instruction
-    pub multisig: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct MintByUser<'info> {
+pub struct InitializeSeizureEscrow<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [SEIZURE_ESCROW_SEED],
+        bump,
+        token::mint = mint,
+        token::authority = token_state,
+        token::token_program = token_program
+    )]
+    pub seizure_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(case_reference: [u8; 32])]
+pub struct Seize<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(mut, address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, token::mint = mint)]
+    pub target_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Blacklist entry PDA for the target owner, must be active
+    #[account(seeds = [BLACKLIST_SEED, target_account.owner.as_ref()], bump)]
+    pub target_owner_blacklist_entry: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [SEIZURE_ESCROW_SEED], bump)]
+    pub seizure_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = SeizureRecord::LEN,
+        seeds = [SEIZURE_SEED, case_reference.as_ref(), target_account.key().as_ref()],
+        bump
+    )]
+    pub seizure_record: Account<'info, SeizureRecord>,
+
+    /// Only needed for `FreezeAndRemint`, on mints without a permanent delegate
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = FreezeRecord::LEN,
+        seeds = [FREEZE_RECORD_SEED, target_account.key().as_ref()],
+        bump
+    )]
+    pub freeze_record: Option<Account<'info, FreezeRecord>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PurchaseItem<'info> {
     pub user: Signer<'info>,
     pub token_state: Account<'info, TokenState>,
-    pub mint: Account<'info, Mint>,
-    pub payment_token: Account<'info, Mint>,
-    pub user_payment_account: Account<'info, TokenAccount>,
-    pub user_token_account: Account<'info, TokenAccount>,
-    pub vault: Account<'info, TokenAccount>,
-    pub blacklist: Account<'info, Blacklist>,
-    /// CHECK: Pyth price feed account is validated in the instruction
-    pub sol_price_feed: UncheckedAccount<'info>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    #[account(address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Blacklist entry PDA for the user, may not exist
+    #[account(seeds = [BLACKLIST_SEED, user.key().as_ref()], bump)]
+    pub user_blacklist_entry: UncheckedAccount<'info>,
+    /// CHECK: KYC record PDA for the user, may not exist
+    #[account(seeds = [KYC_SEED, user.key().as_ref()], bump)]
+    pub user_kyc_record: UncheckedAccount<'info>,
+    #[account(mut, address = token_state.vault @ DiamondTokenError::InvalidTokenAccount)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// CHECK: PDA that owns the fee accounts, holds no data
+    #[account(seeds = [FEE_TREASURY_SEED], bump)]
+    pub fee_treasury: UncheckedAccount<'info>,
+    #[account(mut, token::mint = token_state.mint, token::authority = fee_treasury)]
+    pub fee_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyReserve<'info> {
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// Premint vault, excluded from the circulating supply
+    #[account(address = token_state.vault @ DiamondTokenError::InvalidTokenAccount)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Seizure escrow, excluded from the circulating supply once it exists
+    #[account(seeds = [SEIZURE_ESCROW_SEED], bump)]
+    pub seizure_escrow: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// DIAMOND awaiting a buyback burn, excluded from the circulating supply
+    #[account(token::mint = token_state.mint, token::authority = token_state)]
+    pub buyback_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: PDA that owns the fee accounts, holds no data
+    #[account(seeds = [FEE_TREASURY_SEED], bump)]
+    pub fee_treasury: UncheckedAccount<'info>,
+
+    /// DIAMOND fee account, excluded from the circulating supply
+    #[account(token::mint = token_state.mint, token::authority = fee_treasury)]
+    pub fee_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Pyth SOL/USD feed, only required when a wrapped SOL vault is registered
+    #[account(address = SOL_USD_PRICE_FEED_PUBKEY @ DiamondTokenError::InvalidPriceFeed)]
+    pub sol_price_feed: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeReserveHistory<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = ReserveHistory::LEN,
+        seeds = [RESERVE_HISTORY_SEED],
+        bump
+    )]
+    pub reserve_history: Account<'info, ReserveHistory>,
+
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct AdminBurn<'info> {
-    pub admin: Signer<'info>,
+pub struct RecordReserve<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
     pub token_state: Account<'info, TokenState>,
-    /// CHECK: Multisig account is validated in the instruction
-    pub multisig: UncheckedAccount<'info>,
-    pub mint: Account<'info, Mint>,
-    pub vault: Account<'info, TokenAccount>,
-    pub premint_account: Option<Account<'info, TokenAccount>>,
-    pub refund_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, seeds = [RESERVE_HISTORY_SEED], bump = reserve_history.bump)]
+    pub reserve_history: Account<'info, ReserveHistory>,
+
+    /// Premint vault, excluded from the circulating supply
+    #[account(address = token_state.vault @ DiamondTokenError::InvalidTokenAccount)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Seizure escrow, excluded from the circulating supply once it exists
+    #[account(seeds = [SEIZURE_ESCROW_SEED], bump)]
+    pub seizure_escrow: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// DIAMOND awaiting a buyback burn, excluded from the circulating supply
+    #[account(token::mint = token_state.mint, token::authority = token_state)]
+    pub buyback_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: PDA that owns the fee accounts, holds no data
+    #[account(seeds = [FEE_TREASURY_SEED], bump)]
+    pub fee_treasury: UncheckedAccount<'info>,
+
+    /// DIAMOND fee account, excluded from the circulating supply
+    #[account(token::mint = token_state.mint, token::authority = fee_treasury)]
+    pub fee_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Pyth SOL/USD feed, only required when a wrapped SOL vault is registered
+    #[account(address = SOL_USD_PRICE_FEED_PUBKEY @ DiamondTokenError::InvalidPriceFeed)]
+    pub sol_price_feed: Option<UncheckedAccount<'info>>,
 }
 
 #[derive(Accounts)]
-pub struct Pause<'info> {
-    /// CHECK: Authority is validated in the instruction
-    pub authority: UncheckedAccount<'info>,
+pub struct SetReserveVaults<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
     pub token_state: Account<'info, TokenState>,
-    /// CHECK: Multisig account is validated in the instruction
-    pub multisig: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
 }
 
 #[derive(Accounts)]
-pub struct Unpause<'info> {
-    /// CHECK: Authority is validated in the instruction
-    pub authority: UncheckedAccount<'info>,
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
     pub token_state: Account<'info, TokenState>,
-    /// CHECK: Multisig account is validated in the instruction
-    pub multisig: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Config::LEN,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateMaxSupply<'info> {
-    /// CHECK: Authority is validated in the instruction
-    pub authority: UncheckedAccount<'info>,
+pub struct SetBuybackConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
     pub token_state: Account<'info, TokenState>,
-    /// CHECK: Multisig account is validated in the instruction
-    pub multisig: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(token::authority = token_state)]
+    pub usdt_reserve: InterfaceAccount<'info, TokenAccount>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateBlacklist<'info> {
-    /// CHECK: Authority is validated in the instruction
-    pub authority: UncheckedAccount<'info>,
+pub struct SetFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
     pub token_state: Account<'info, TokenState>,
 
-    #[account(seeds = [BLACKLIST_SEED], bump)]
-    pub blacklist: Account<'info, Blacklist>,
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
 
-    /// CHECK: Multisig account is validated in the instruction
-    pub multisig: UncheckedAccount<'info>,
+#[derive(Accounts)]
+pub struct SetGeoAttestation<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
 }
 
 #[derive(Accounts)]
-pub struct PurchaseItem<'info> {
-    pub user: Signer<'info>,
+pub struct SetDeniedJurisdictions<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct SetVolumeTiers<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
     pub token_state: Account<'info, TokenState>,
-    pub user_token_account: Account<'info, TokenAccount>,
-    pub vault: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
 }
 
 #[derive(Accounts)]
-pub struct TransferHook<'info> {
-    #[account(seeds = [BLACKLIST_SEED], bump)]
-    pub blacklist: Account<'info, Blacklist>,
-    pub source: Account<'info, TokenAccount>,
-    pub destination: Account<'info, TokenAccount>,
+pub struct QuoteMint<'info> {
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    pub payment_token: InterfaceAccount<'info, Mint>,
+    /// CHECK: Pyth SOL/USD feed, only required for wrapped SOL payments
+    #[account(address = SOL_USD_PRICE_FEED_PUBKEY @ DiamondTokenError::InvalidPriceFeed)]
+    pub sol_price_feed: Option<UncheckedAccount<'info>>,
 }
 
 #[derive(Accounts)]
-pub struct VerifyReserve<'info> {
+pub struct ManageTransferFee<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(mut, address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTransferFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(mut, address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: PDA that owns the fee accounts, holds no data
+    #[account(seeds = [FEE_TREASURY_SEED], bump)]
+    pub fee_treasury: UncheckedAccount<'info>,
+
+    #[account(mut, token::mint = mint, token::authority = fee_treasury)]
+    pub fee_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
+    pub token_state: Account<'info, TokenState>,
+
+    /// CHECK: PDA that owns the fee accounts, holds no data
+    #[account(seeds = [FEE_TREASURY_SEED], bump)]
+    pub fee_treasury: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, token::mint = mint, token::authority = fee_treasury)]
+    pub fee_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = mint)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct BuybackAndBurn<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [TOKEN_STATE_SEED], bump = token_state.bump)]
     pub token_state: Account<'info, TokenState>,
-    pub vault: Account<'info, TokenAccount>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, address = token_state.mint @ DiamondTokenError::InvalidTokenAccount)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        address = config.usdt_reserve @ DiamondTokenError::InvalidReserveAccount,
+        constraint = config.reserve_vaults.contains(&usdt_reserve.key())
+            @ DiamondTokenError::InvalidReserveAccount
+    )]
+    pub usdt_reserve: InterfaceAccount<'info, TokenAccount>,
+
+    /// Premint vault, excluded from the circulating supply
+    #[account(address = token_state.vault @ DiamondTokenError::InvalidTokenAccount)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Seizure escrow, excluded from the circulating supply once it exists
+    #[account(seeds = [SEIZURE_ESCROW_SEED], bump)]
+    pub seizure_escrow: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: PDA that owns the fee accounts, holds no data
+    #[account(seeds = [FEE_TREASURY_SEED], bump)]
+    pub fee_treasury: UncheckedAccount<'info>,
+
+    /// DIAMOND fee account, excluded from the circulating supply
+    #[account(token::mint = token_state.mint, token::authority = fee_treasury)]
+    pub fee_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Pyth SOL/USD feed, only required when a wrapped SOL vault is registered
+    #[account(address = SOL_USD_PRICE_FEED_PUBKEY @ DiamondTokenError::InvalidPriceFeed)]
+    pub sol_price_feed: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = token_state
+    )]
+    pub buyback_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Signs the swap as delegate of the USDT reserve for `usdt_amount` only
+    #[account(seeds = [BUYBACK_AUTHORITY_SEED], bump)]
+    pub buyback_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Must be the swap program stored in the config
+    #[account(
+        executable,
+        address = config.swap_program @ DiamondTokenError::InvalidSwapProgram
+    )]
+    pub swap_program: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Program of the USDT reserve, which need not match the DIAMOND mint's
+    pub reserve_token_program: Interface<'info, TokenInterface>,
 }
@@ -0,0 +1,240 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_interface::Mint;
+use pyth_sdk_solana::{state::SolanaPriceAccount, Price};
+
+use crate::{constants::*, error::*, state::*};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct MintQuote {
+    pub payment_amount: u64, // Goes to the vault, after the volume discount
+    pub fee_amount: u64,     // Goes to the fee treasury, on top of payment_amount
+    pub discount_bps: u16,   // Volume discount that was applied
+}
+
+/// Prices `amount` DIAMOND base units in `payment_token`. Used by both
+/// `mint_by_user` and `quote_mint` so quotes always match what is charged.
+pub fn quote_mint(
+    amount: u64,
+    payment_token: &InterfaceAccount<Mint>,
+    sol_price_feed: Option<&AccountInfo>,
+    config: &Config,
+) -> Result<MintQuote> {
+    require!(amount > 0, DiamondTokenError::InvalidAmount);
+
+    let (base_payment, min_payment) = match payment_token.key() {
+        USDT_PUBKEY => (
+            stablecoin_payment(amount, TOKEN_PRICE_USDT)?,
+            MIN_PURCHASE_USDC,
+        ),
+        USDC_PUBKEY => (
+            stablecoin_payment(amount, TOKEN_PRICE_USDC)?,
+            MIN_PURCHASE_USDC,
+        ),
+        spl_token::native_mint::ID | spl_token_2022::native_mint::ID => (
+            sol_payment(amount, &sol_usd_price(sol_price_feed)?)?,
+            MIN_PURCHASE_SOL,
+        ),
+        _ => return Err(DiamondTokenError::UnsupportedPaymentToken.into()),
+    };
+
+    // Same discount for every payment token
+    let discount_bps = config.volume_discount_bps(amount);
+    let payment_amount = base_payment
+        .checked_sub(Config::bps_of(base_payment, discount_bps)?)
+        .ok_or(DiamondTokenError::MathOverflow)?;
+
+    require!(
+        payment_amount >= min_payment,
+        DiamondTokenError::PurchaseAmountTooSmall
+    );
+
+    Ok(MintQuote {
+        payment_amount,
+        fee_amount: Config::bps_of(payment_amount, config.buy_fee_bps)?,
+        discount_bps,
+    })
+}
+
+/// `amount` is in DIAMOND base units, `price` in payment base units per whole token.
+fn stablecoin_payment(amount: u64, price: u64) -> Result<u64> {
+    let payment = (amount as u128)
+        .checked_mul(price as u128)
+        .ok_or(DiamondTokenError::MathOverflow)?
+        .checked_div(10u128.pow(DECIMALS as u32))
+        .ok_or(DiamondTokenError::MathOverflow)?;
+
+    u64::try_from(payment).map_err(|_| DiamondTokenError::MathOverflow.into())
+}
+
+/// USD value (6 decimals) of `amount` DIAMOND base units at `TOKEN_PRICE_USDT`.
+pub fn diamond_usd_value(amount: u64) -> Result<u64> {
+    stablecoin_payment(amount, TOKEN_PRICE_USDT)
+}
+
+/// USD value (6 decimals) of `lamports` at the current Pyth SOL/USD price.
+pub fn sol_usd_value(lamports: u64, sol_price_feed: Option<&AccountInfo>) -> Result<u64> {
+    lamports_usd_value(lamports, &sol_usd_price(sol_price_feed)?)
+}
+
+/// USD value (6 decimals) of `lamports` at `current_price` (SOL/USD).
+fn lamports_usd_value(lamports: u64, current_price: &Price) -> Result<u64> {
+    // usd = lamports / 10^SOL_DECIMALS * price * 10^expo, in USDT base units
+    let mut numerator = (lamports as u128)
+        .checked_mul(current_price.price as u128)
+        .ok_or(DiamondTokenError::MathOverflow)?
+        .checked_mul(10u128.pow(USDT_DECIMALS as u32))
+        .ok_or(DiamondTokenError::MathOverflow)?;
+    let mut denominator = 10u128.pow(SOL_DECIMALS as u32);
+
+    let scale = 10u128
+        .checked_pow(current_price.expo.unsigned_abs())
+        .ok_or(DiamondTokenError::MathOverflow)?;
+    if current_price.expo < 0 {
+        denominator = denominator
+            .checked_mul(scale)
+            .ok_or(DiamondTokenError::MathOverflow)?;
+    } else {
+        numerator = numerator
+            .checked_mul(scale)
+            .ok_or(DiamondTokenError::MathOverflow)?;
+    }
+
+    let usd = numerator
+        .checked_div(denominator)
+        .ok_or(DiamondTokenError::MathOverflow)?;
+
+    u64::try_from(usd).map_err(|_| DiamondTokenError::MathOverflow.into())
+}
+
+/// Fresh, positive SOL/USD price from the Pyth feed, which callers only
+/// have to pass when a SOL amount is priced.
+fn sol_usd_price(sol_price_feed: Option<&AccountInfo>) -> Result<Price> {
+    let sol_price_feed = sol_price_feed.ok_or(DiamondTokenError::InvalidPriceFeed)?;
+    let price_feed = SolanaPriceAccount::account_info_to_feed(sol_price_feed)
+        .map_err(|_| DiamondTokenError::InvalidPriceFeed)?;
+
+    let current_time = Clock::get()?.unix_timestamp;
+    let current_price = price_feed
+        .get_price_no_older_than(current_time, MAX_PRICE_AGE as u64)
+        .ok_or(DiamondTokenError::InvalidPriceFeed)?;
+
+    require!(current_price.price > 0, DiamondTokenError::InvalidPriceFeed);
+
+    Ok(current_price)
+}
+
+/// Lamports needed to pay `TOKEN_PRICE_USD` per token at `current_price` (SOL/USD).
+fn sol_payment(amount: u64, current_price: &Price) -> Result<u64> {
+    // lamports = amount * usd_per_token / usd_per_sol, with usd_per_sol = price * 10^expo
+    let mut numerator = (amount as u128)
+        .checked_mul(TOKEN_PRICE_USD as u128)
+        .ok_or(DiamondTokenError::MathOverflow)?;
+    let mut denominator = (current_price.price as u128)
+        .checked_mul(10u128.pow(USDT_DECIMALS as u32))
+        .ok_or(DiamondTokenError::MathOverflow)?;
+
+    let scale = 10u128
+        .checked_pow(current_price.expo.unsigned_abs())
+        .ok_or(DiamondTokenError::MathOverflow)?;
+    if current_price.expo < 0 {
+        numerator = numerator
+            .checked_mul(scale)
+            .ok_or(DiamondTokenError::MathOverflow)?;
+    } else {
+        denominator = denominator
+            .checked_mul(scale)
+            .ok_or(DiamondTokenError::MathOverflow)?;
+    }
+
+    let lamports = numerator
+        .checked_div(denominator)
+        .ok_or(DiamondTokenError::MathOverflow)?;
+
+    u64::try_from(lamports).map_err(|_| DiamondTokenError::MathOverflow.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sol_price(price: i64, expo: i32) -> Price {
+        Price {
+            price,
+            conf: 0,
+            expo,
+            publish_time: 0,
+        }
+    }
+
+    #[test]
+    fn stablecoin_payment_prices_whole_tokens() {
+        // Before this change one whole token cost 10^DECIMALS times its price
+        let one_token = 10u64.pow(DECIMALS as u32);
+        let old_payment = one_token.checked_mul(TOKEN_PRICE_USDT).unwrap();
+        let new_payment = stablecoin_payment(one_token, TOKEN_PRICE_USDT).unwrap();
+        assert_eq!(new_payment, TOKEN_PRICE_USDT);
+        assert_eq!(old_payment, new_payment * one_token);
+    }
+
+    #[test]
+    fn stablecoin_payment_scales_diamond_decimals() {
+        let one_token = 10u64.pow(DECIMALS as u32);
+        assert_eq!(
+            stablecoin_payment(one_token, TOKEN_PRICE_USDT).unwrap(),
+            1_000_000
+        );
+        assert_eq!(
+            stablecoin_payment(1_000 * one_token, TOKEN_PRICE_USDC).unwrap(),
+            800_000_000
+        );
+        // Fractions of a cent round down
+        assert_eq!(stablecoin_payment(999, TOKEN_PRICE_USDT).unwrap(), 0);
+        assert!(stablecoin_payment(u64::MAX, u64::MAX).is_err());
+    }
+
+    #[test]
+    fn sol_payment_handles_negative_exponent() {
+        // 1 token at 0.8 USD with SOL at 160 USD (Pyth expo -8) is 0.005 SOL
+        let one_token = 10u64.pow(DECIMALS as u32);
+        assert_eq!(
+            sol_payment(one_token, &sol_price(16_000_000_000, -8)).unwrap(),
+            5_000_000
+        );
+    }
+
+    #[test]
+    fn sol_payment_handles_non_negative_exponent() {
+        let one_token = 10u64.pow(DECIMALS as u32);
+        assert_eq!(
+            sol_payment(one_token, &sol_price(160, 0)).unwrap(),
+            5_000_000
+        );
+        assert_eq!(
+            sol_payment(one_token, &sol_price(16, 1)).unwrap(),
+            5_000_000
+        );
+    }
+
+    #[test]
+    fn lamports_usd_value_scales_by_exponent() {
+        // 2 SOL at 160 USD is 320 USD, whichever exponent Pyth reports
+        let two_sol = 2 * 10u64.pow(SOL_DECIMALS as u32);
+        assert_eq!(
+            lamports_usd_value(two_sol, &sol_price(16_000_000_000, -8)).unwrap(),
+            320_000_000
+        );
+        assert_eq!(
+            lamports_usd_value(two_sol, &sol_price(160, 0)).unwrap(),
+            320_000_000
+        );
+        assert_eq!(
+            lamports_usd_value(two_sol, &sol_price(16, 1)).unwrap(),
+            320_000_000
+        );
+        // Below a micro-dollar rounds down
+        assert_eq!(lamports_usd_value(1, &sol_price(160, 0)).unwrap(), 0);
+        assert!(lamports_usd_value(u64::MAX, &sol_price(i64::MAX, 10)).is_err());
+    }
+}
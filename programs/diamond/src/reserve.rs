@@ -0,0 +1,210 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{constants::*, error::*, pricing, state::*};
+
+/// Snapshot of the DIAMOND liability against the registered reserve vaults.
+/// All USD amounts use 6 decimals.
+pub struct ReserveAttestation {
+    pub total_supply: u64,
+    pub circulating_supply: u64, // effective supply minus the program's own DIAMOND
+    pub liability_usd: u64,
+    pub reserve_usd: u64,
+    pub reserve_ratio_bps: u64, // u64::MAX when nothing is circulating
+    pub assets: Vec<ReserveAsset>,
+}
+
+/// Loads `vault` as a reserve vault: a token account held by the token state
+/// PDA in an asset that can be valued in USD.
+pub fn load_vault<'info>(
+    vault: &'info AccountInfo<'info>,
+    token_state: &Pubkey,
+) -> Result<InterfaceAccount<'info, TokenAccount>> {
+    let vault = InterfaceAccount::<TokenAccount>::try_from(vault)
+        .map_err(|_| DiamondTokenError::InvalidReserveAccount)?;
+
+    require_keys_eq!(
+        vault.owner,
+        *token_state,
+        DiamondTokenError::InvalidReserveAccount
+    );
+    require!(
+        is_stablecoin(&vault.mint) || is_wrapped_sol(&vault.mint),
+        DiamondTokenError::UnsupportedReserveAsset
+    );
+
+    Ok(vault)
+}
+
+/// Values every vault in `config.reserve_vaults`, which must be passed in
+/// `vaults` in the same order, and compares the total with the circulating
+/// supply at `TOKEN_PRICE_USDT`.
+///
+/// `held` are the DIAMOND accounts the program holds itself: the premint
+/// vault, seizure escrow, buyback and fee accounts, already checked by the
+/// caller's account constraints. Those passed are left out of the
+/// circulating supply; those omitted count as circulating.
+pub fn attest<'info>(
+    token_state: &Account<'info, TokenState>,
+    held: &[Option<&InterfaceAccount<'info, TokenAccount>>],
+    config: &Config,
+    vaults: &'info [AccountInfo<'info>],
+    sol_price_feed: Option<&AccountInfo>,
+) -> Result<ReserveAttestation> {
+    require!(
+        vaults.len() == config.reserve_vaults.len(),
+        DiamondTokenError::InvalidReserveAccount
+    );
+
+    let mut assets = Vec::with_capacity(vaults.len());
+    let mut reserve_usd: u64 = 0;
+    for (vault, expected) in vaults.iter().zip(config.reserve_vaults.iter()) {
+        require_keys_eq!(
+            vault.key(),
+            *expected,
+            DiamondTokenError::InvalidReserveAccount
+        );
+        let vault = load_vault(vault, &token_state.key())?;
+
+        // Stablecoins share the 6 decimals of the USD unit
+        let usd_value = if is_stablecoin(&vault.mint) {
+            vault.amount
+        } else {
+            pricing::sol_usd_value(vault.amount, sol_price_feed)?
+        };
+
+        reserve_usd = reserve_usd
+            .checked_add(usd_value)
+            .ok_or(DiamondTokenError::MathOverflow)?;
+        assets.push(ReserveAsset {
+            mint: vault.mint,
+            amount: vault.amount,
+            usd_value,
+        });
+    }
+
+    let held: Vec<(Pubkey, u64)> = held
+        .iter()
+        .flatten()
+        .map(|account| (account.key(), account.amount))
+        .collect();
+    let circulating_supply = circulating_supply(token_state.effective_supply(), &held)?;
+    let liability_usd = pricing::diamond_usd_value(circulating_supply)?;
+
+    Ok(ReserveAttestation {
+        total_supply: token_state.total_supply,
+        circulating_supply,
+        liability_usd,
+        reserve_usd,
+        reserve_ratio_bps: reserve_ratio_bps(reserve_usd, liability_usd)?,
+        assets,
+    })
+}
+
+/// `supply` less the `(account, balance)` pairs in `held`. An account passed
+/// twice is refused rather than subtracted twice.
+pub fn circulating_supply(supply: u64, held: &[(Pubkey, u64)]) -> Result<u64> {
+    let mut circulating = supply;
+    for (index, (account, amount)) in held.iter().enumerate() {
+        require!(
+            !held[..index].iter().any(|(other, _)| other == account),
+            DiamondTokenError::InvalidTokenAccount
+        );
+        circulating = circulating.saturating_sub(*amount);
+    }
+
+    Ok(circulating)
+}
+
+/// Reserve over liability in basis points, saturating at `u64::MAX`, which
+/// also stands for no liability at all.
+pub fn reserve_ratio_bps(reserve_usd: u64, liability_usd: u64) -> Result<u64> {
+    if liability_usd == 0 {
+        return Ok(u64::MAX);
+    }
+
+    let ratio = (reserve_usd as u128)
+        .checked_mul(BPS_DENOMINATOR as u128)
+        .ok_or(DiamondTokenError::MathOverflow)?
+        .checked_div(liability_usd as u128)
+        .ok_or(DiamondTokenError::MathOverflow)?;
+
+    Ok(u64::try_from(ratio).unwrap_or(u64::MAX))
+}
+
+impl ReserveAttestation {
+    /// Reserve value above the liability, the most a buyback may spend.
+    pub fn surplus_usd(&self) -> u64 {
+        self.reserve_usd.saturating_sub(self.liability_usd)
+    }
+
+    pub fn into_snapshot(self, caller: Pubkey, timestamp: i64) -> ReserveSnapshot {
+        ReserveSnapshot {
+            timestamp,
+            total_supply: self.total_supply,
+            circulating_supply: self.circulating_supply,
+            liability_usd: self.liability_usd,
+            reserve_usd: self.reserve_usd,
+            reserve_ratio_bps: self.reserve_ratio_bps,
+            caller,
+            assets: self.assets,
+        }
+    }
+}
+
+fn is_stablecoin(mint: &Pubkey) -> bool {
+    *mint == USDT_PUBKEY || *mint == USDC_PUBKEY
+}
+
+fn is_wrapped_sol(mint: &Pubkey) -> bool {
+    *mint == spl_token::native_mint::ID || *mint == spl_token_2022::native_mint::ID
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reserve_ratio_bps_compares_reserve_with_liability() {
+        assert_eq!(reserve_ratio_bps(1_000, 1_000).unwrap(), BPS_DENOMINATOR);
+        assert_eq!(reserve_ratio_bps(1_500, 1_000).unwrap(), 15_000);
+        assert_eq!(reserve_ratio_bps(0, 1_000).unwrap(), 0);
+        // Rounds down
+        assert_eq!(reserve_ratio_bps(1, 3).unwrap(), 3_333);
+    }
+
+    #[test]
+    fn circulating_supply_leaves_out_held_accounts() {
+        let (vault, escrow, buyback, fees) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        assert_eq!(circulating_supply(1_000, &[]).unwrap(), 1_000);
+        assert_eq!(
+            circulating_supply(
+                1_000,
+                &[(vault, 400), (escrow, 50), (buyback, 30), (fees, 20)]
+            )
+            .unwrap(),
+            500
+        );
+        // Never below zero
+        assert_eq!(circulating_supply(100, &[(vault, 400)]).unwrap(), 0);
+        // The same account cannot be subtracted twice
+        assert_eq!(
+            circulating_supply(1_000, &[(vault, 400), (vault, 400)]).unwrap_err(),
+            DiamondTokenError::InvalidTokenAccount.into()
+        );
+    }
+
+    #[test]
+    fn reserve_ratio_bps_saturates() {
+        assert_eq!(reserve_ratio_bps(0, 0).unwrap(), u64::MAX);
+        assert_eq!(reserve_ratio_bps(u64::MAX, 0).unwrap(), u64::MAX);
+        assert_eq!(reserve_ratio_bps(u64::MAX, 1).unwrap(), u64::MAX);
+    }
+}
@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::{constants::*, error::*};
+
 #[account]
 pub struct TokenState {
     pub authority: Pubkey,         // 32 bytes
@@ -10,6 +12,7 @@ pub struct TokenState {
     pub last_pause_timestamp: i64, // 8 bytes
     pub multisig: Pubkey,          // 32 bytes
     pub vault: Pubkey,             // 32 bytes
+    pub seized_locked: u64,        // 8 bytes, locked in place by FreezeAndRemint seizures
     pub bump: u8,                  // 1 byte
 }
 
@@ -23,13 +26,130 @@ impl TokenState {
         8 + // last_pause_timestamp
         32 + // multisig
         32 + // vault
+        8 + // seized_locked
         1; // bump
 
+    /// Supply counted against `max_supply`. Balances locked by a
+    /// `FreezeAndRemint` seizure were minted again into escrow, so the locked
+    /// originals are left out rather than counted twice.
+    pub fn effective_supply(&self) -> u64 {
+        self.total_supply.saturating_sub(self.seized_locked)
+    }
+
     pub fn is_admin(&self, admin: &Pubkey) -> bool {
         self.authority == *admin
     }
+
+    pub fn require_admin(&self, admin: &Pubkey) -> Result<()> {
+        require!(self.is_admin(admin), DiamondTokenError::NotAuthorized);
+        Ok(())
+    }
 }
 
+#[account]
+pub struct Config {
+    pub swap_program: Pubkey,                          // 32 bytes
+    pub usdt_reserve: Pubkey,                          // 32 bytes
+    pub buy_fee_bps: u16,                              // 2 bytes, charged on mint_by_user payments
+    pub redeem_fee_bps: u16,                           // 2 bytes, charged on redemptions
+    pub purchase_fee_bps: u16,                         // 2 bytes, charged on purchase_item
+    pub volume_tiers: Vec<VolumeTier>,                 // Ascending by min_amount
+    pub auto_freeze_on_blacklist: bool,                // 1 byte
+    pub compliance_mode: ComplianceMode,               // 1 byte
+    pub kyc_attester: Pubkey,                          // 32 bytes, issues KycRecords
+    pub kyc_tier_limits: [TierLimits; KYC_TIER_COUNT], // Indexed by KycTier
+    pub compliance_signer: Pubkey,                     // 32 bytes, signs geo attestations
+    pub require_geo_attestation: bool,                 // 1 byte
+    pub denied_jurisdictions: Vec<[u8; 2]>,            // ISO 3166-1 alpha-2 codes
+    pub pending_interest_rate: Option<PendingRate>,    // Awaiting the timelock
+    pub reserve_vaults: Vec<Pubkey>,                   // Valued by verify_reserve
+    pub kyc_exempt_owners: Vec<Pubkey>,                // Need no KYC, e.g. AMM pool authorities
+    pub bump: u8,                                      // 1 byte
+}
+
+impl Config {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // swap_program
+        32 + // usdt_reserve
+        2 + // buy_fee_bps
+        2 + // redeem_fee_bps
+        2 + // purchase_fee_bps
+        4 + MAX_VOLUME_TIERS * VolumeTier::LEN + // volume_tiers
+        1 + // auto_freeze_on_blacklist
+        1 + // compliance_mode
+        32 + // kyc_attester
+        KYC_TIER_COUNT * TierLimits::LEN + // kyc_tier_limits
+        32 + // compliance_signer
+        1 + // require_geo_attestation
+        4 + MAX_DENIED_JURISDICTIONS * 2 + // denied_jurisdictions
+        1 + PendingRate::LEN + // pending_interest_rate
+        4 + MAX_RESERVE_VAULTS * 32 + // reserve_vaults
+        4 + MAX_KYC_EXEMPT_OWNERS * 32 + // kyc_exempt_owners
+        1; // bump
+
+    /// `bps` basis points of `amount`, rounded down.
+    pub fn bps_of(amount: u64, bps: u16) -> Result<u64> {
+        let value = (amount as u128)
+            .checked_mul(bps as u128)
+            .ok_or(DiamondTokenError::MathOverflow)?
+            .checked_div(BPS_DENOMINATOR as u128)
+            .ok_or(DiamondTokenError::MathOverflow)?;
+
+        u64::try_from(value).map_err(|_| DiamondTokenError::MathOverflow.into())
+    }
+
+    pub fn tier_limits(&self, tier: KycTier) -> &TierLimits {
+        &self.kyc_tier_limits[tier as usize]
+    }
+
+    /// Discount of the highest tier whose threshold `amount` reaches.
+    pub fn volume_discount_bps(&self, amount: u64) -> u16 {
+        self.volume_tiers
+            .iter()
+            .rev()
+            .find(|tier| amount >= tier.min_amount)
+            .map_or(0, |tier| tier.discount_bps)
+    }
+
+    /// Tiers must be ascending by threshold with non-decreasing, capped discounts.
+    pub fn validate_volume_tiers(tiers: &[VolumeTier]) -> Result<()> {
+        require!(
+            tiers.len() <= MAX_VOLUME_TIERS,
+            DiamondTokenError::InvalidVolumeTiers
+        );
+
+        let mut previous: Option<&VolumeTier> = None;
+        for tier in tiers {
+            require!(
+                tier.min_amount > 0 && tier.discount_bps <= MAX_VOLUME_DISCOUNT_BPS,
+                DiamondTokenError::InvalidVolumeTiers
+            );
+            if let Some(previous) = previous {
+                require!(
+                    tier.min_amount > previous.min_amount
+                        && tier.discount_bps >= previous.discount_bps,
+                    DiamondTokenError::InvalidVolumeTiers
+                );
+            }
+            previous = Some(tier);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct VolumeTier {
+    pub min_amount: u64,   // 8 bytes, DIAMOND base units
+    pub discount_bps: u16, // 2 bytes
+}
+
+impl VolumeTier {
+    pub const LEN: usize = 8 + 2;
+}
+
+/// Legacy single-account blacklist, kept only so it can be migrated to
+/// `BlacklistEntry` PDAs with `migrate_blacklist`.
 #[account]
 pub struct Blacklist {
     pub addresses: Vec<Pubkey>, // Vector of blacklisted addresses
@@ -44,3 +164,724 @@ impl Blacklist {
         1 // bump
     }
 }
+
+/// `DenyList` admits everyone not blacklisted; `AllowList` additionally
+/// requires a valid `KycRecord` to mint, purchase or receive transfers.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ComplianceMode {
+    DenyList,
+    AllowList,
+}
+
+/// Ordered by verification depth.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum KycTier {
+    Unverified,
+    Basic,
+    Enhanced,
+}
+
+/// Interest rate proposed for the interest-bearing mint extension.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct PendingRate {
+    pub rate_bps: i16,      // 2 bytes, yearly rate
+    pub executable_at: i64, // 8 bytes
+}
+
+impl PendingRate {
+    pub const LEN: usize = 2 + 8;
+}
+
+/// Mint limits for one KYC tier, in DIAMOND base units.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct TierLimits {
+    pub daily_limit: u64,    // 8 bytes
+    pub lifetime_limit: u64, // 8 bytes
+}
+
+impl TierLimits {
+    pub const LEN: usize = 8 + 8;
+
+    pub const UNLIMITED: TierLimits = TierLimits {
+        daily_limit: u64::MAX,
+        lifetime_limit: u64::MAX,
+    };
+}
+
+/// Per-wallet mint counters, seeded by `USER_VOLUME_SEED` + wallet.
+#[account]
+pub struct UserVolume {
+    pub wallet: Pubkey,       // 32 bytes
+    pub day: i64,             // 8 bytes, unix day of daily_amount
+    pub daily_amount: u64,    // 8 bytes
+    pub lifetime_amount: u64, // 8 bytes
+    pub last_geo_nonce: u64,  // 8 bytes, highest geo attestation nonce used
+    pub bump: u8,             // 1 byte
+}
+
+impl UserVolume {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // wallet
+        8 + // day
+        8 + // daily_amount
+        8 + // lifetime_amount
+        8 + // last_geo_nonce
+        1; // bump
+
+    /// Adds `amount` to the counters, resetting the daily one on a new day,
+    /// and fails if either would exceed `limits`.
+    pub fn record(&mut self, amount: u64, limits: &TierLimits, now: i64) -> Result<()> {
+        let day = now.div_euclid(SECONDS_PER_DAY);
+        let daily_amount = if day == self.day {
+            self.daily_amount
+        } else {
+            0
+        }
+        .checked_add(amount)
+        .ok_or(DiamondTokenError::MathOverflow)?;
+        let lifetime_amount = self
+            .lifetime_amount
+            .checked_add(amount)
+            .ok_or(DiamondTokenError::MathOverflow)?;
+
+        require!(
+            daily_amount <= limits.daily_limit && lifetime_amount <= limits.lifetime_limit,
+            DiamondTokenError::KycLimitExceeded
+        );
+
+        self.day = day;
+        self.daily_amount = daily_amount;
+        self.lifetime_amount = lifetime_amount;
+
+        Ok(())
+    }
+
+    /// Attestation nonces must strictly increase per wallet, so each
+    /// signature can be used once.
+    pub fn use_geo_nonce(&mut self, nonce: u64) -> Result<()> {
+        require!(
+            nonce > self.last_geo_nonce,
+            DiamondTokenError::GeoNonceReused
+        );
+        self.last_geo_nonce = nonce;
+
+        Ok(())
+    }
+}
+
+/// One PDA per verified wallet, seeded by `KYC_SEED` + wallet.
+#[account]
+pub struct KycRecord {
+    pub wallet: Pubkey,   // 32 bytes
+    pub tier: KycTier,    // 1 byte
+    pub expires_at: i64,  // 8 bytes
+    pub attester: Pubkey, // 32 bytes
+    pub issued_at: i64,   // 8 bytes
+    pub bump: u8,         // 1 byte
+}
+
+impl KycRecord {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // wallet
+        1 + // tier
+        8 + // expires_at
+        32 + // attester
+        8 + // issued_at
+        1; // bump
+
+    /// The attested tier, or `Unverified` once the record has expired.
+    pub fn tier_at(&self, now: i64) -> KycTier {
+        if now < self.expires_at {
+            self.tier
+        } else {
+            KycTier::Unverified
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum BlacklistReason {
+    Sanctions,
+    Fraud,
+    CourtOrder,
+    Internal,
+}
+
+/// How batch instructions treat addresses that are already in the requested state.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateMode {
+    Skip,
+    Fail,
+}
+
+/// One PDA per blacklisted address, seeded by `BLACKLIST_SEED` + address.
+#[account]
+pub struct BlacklistEntry {
+    pub address: Pubkey,          // 32 bytes
+    pub reason: BlacklistReason,  // 1 byte
+    pub expires_at: Option<i64>,  // 1 + 8 bytes, None never expires
+    pub added_by: Pubkey,         // 32 bytes
+    pub added_at: i64,            // 8 bytes
+    pub case_reference: [u8; 32], // 32 bytes, hash of the off-chain case file
+    pub bump: u8,                 // 1 byte
+}
+
+impl BlacklistEntry {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // address
+        1 + // reason
+        1 + 8 + // expires_at
+        32 + // added_by
+        8 + // added_at
+        32 + // case_reference
+        1; // bump
+
+    pub fn is_active(&self, now: i64) -> bool {
+        match self.expires_at {
+            Some(expires_at) => now < expires_at,
+            None => true,
+        }
+    }
+}
+
+/// Token metadata field, mirroring the SPL token-metadata interface.
+/// `Key` holds additional fields such as "reserve_attestation_uri".
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum MetadataField {
+    Name,
+    Symbol,
+    Uri,
+    Key(String),
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SeizureMethod {
+    /// Token-2022 mint with the token state PDA as permanent delegate:
+    /// the balance is transferred into the seizure escrow.
+    PermanentDelegate,
+    /// Classic SPL mint: the holder account is frozen with its balance locked
+    /// in place and the same amount is re-minted into the seizure escrow.
+    /// `FreezeRecord::seized_amount` keeps the account frozen for good.
+    FreezeAndRemint,
+}
+
+/// Why the program froze a DIAMOND token account, seeded by `FREEZE_RECORD_SEED`
+/// + token account. The account is held frozen while any reason is set; accounts
+/// frozen by the mint's default state have no reason recorded.
+#[account]
+pub struct FreezeRecord {
+    pub token_account: Pubkey, // 32 bytes
+    pub governance: bool,      // 1 byte, set by freeze_account
+    pub blacklist: bool,       // 1 byte, set by auto-freeze on blacklist
+    pub seized_amount: u64,    // 8 bytes, locked by FreezeAndRemint seizures
+    pub bump: u8,              // 1 byte
+}
+
+impl FreezeRecord {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // token_account
+        1 + // governance
+        1 + // blacklist
+        8 + // seized_amount
+        1; // bump
+
+    pub fn is_held(&self) -> bool {
+        self.governance || self.blacklist || self.seized_amount > 0
+    }
+
+    /// Marks the account as held by a blacklisting and returns whether it was
+    /// marked. An account that is already `frozen` is only marked on top of
+    /// another recorded hold, so that lifting that hold leaves it frozen.
+    pub fn hold_for_blacklist(&mut self, frozen: bool) -> bool {
+        if frozen && !self.is_held() {
+            return false;
+        }
+
+        self.blacklist = true;
+        true
+    }
+}
+
+/// One record per (case, target account), written by `seize`.
+#[account]
+pub struct SeizureRecord {
+    pub case_reference: [u8; 32], // 32 bytes
+    pub target_account: Pubkey,   // 32 bytes
+    pub owner: Pubkey,            // 32 bytes
+    pub amount: u64,              // 8 bytes
+    pub method: SeizureMethod,    // 1 byte
+    pub authority: Pubkey,        // 32 bytes
+    pub timestamp: i64,           // 8 bytes
+    pub bump: u8,                 // 1 byte
+}
+
+impl SeizureRecord {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // case_reference
+        32 + // target_account
+        32 + // owner
+        8 + // amount
+        1 + // method
+        32 + // authority
+        8 + // timestamp
+        1; // bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ScheduledReduction {
+    pub timestamp: i64,  // 8 bytes
+    pub max_supply: u64, // 8 bytes
+}
+
+impl ScheduledReduction {
+    pub const LEN: usize = 8 + 8;
+}
+
+#[account]
+pub struct MaxSupplySchedule {
+    pub reductions: Vec<ScheduledReduction>, // Ordered by timestamp, strictly decreasing targets
+    pub next_index: u32,                     // 4 bytes, first reduction not yet applied
+    pub bump: u8,                            // 1 byte
+}
+
+impl MaxSupplySchedule {
+    pub fn space(max_reductions: usize) -> usize {
+        8 + // discriminator
+        4 + // vec length
+        max_reductions * ScheduledReduction::LEN + // reductions
+        4 + // next_index
+        1 // bump
+    }
+
+    /// Appends reductions after the last scheduled one. Timestamps must be in the
+    /// future and strictly increasing, targets strictly below the previous target.
+    /// Reductions already applied are dropped first, so only pending ones
+    /// count towards `MAX_SCHEDULED_REDUCTIONS`.
+    pub fn append(
+        &mut self,
+        reductions: &[ScheduledReduction],
+        current_max_supply: u64,
+        now: i64,
+    ) -> Result<()> {
+        require!(
+            !reductions.is_empty(),
+            DiamondTokenError::InvalidMaxSupplySchedule
+        );
+
+        let (mut last_timestamp, mut last_max_supply) = match self.reductions.last() {
+            Some(last) => (
+                last.timestamp.max(now),
+                last.max_supply.min(current_max_supply),
+            ),
+            None => (now, current_max_supply),
+        };
+
+        let applied = (self.next_index as usize).min(self.reductions.len());
+        self.reductions.drain(..applied);
+        self.next_index = 0;
+        require!(
+            self.reductions.len() + reductions.len() <= MAX_SCHEDULED_REDUCTIONS,
+            DiamondTokenError::MaxSupplyScheduleFull
+        );
+
+        for reduction in reductions {
+            require!(
+                reduction.timestamp > last_timestamp && reduction.max_supply < last_max_supply,
+                DiamondTokenError::InvalidMaxSupplySchedule
+            );
+            last_timestamp = reduction.timestamp;
+            last_max_supply = reduction.max_supply;
+            self.reductions.push(*reduction);
+        }
+
+        Ok(())
+    }
+}
+
+/// Balance of one reserve vault and its value in USD (6 decimals).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct ReserveAsset {
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub usd_value: u64,
+}
+
+impl ReserveAsset {
+    pub const LEN: usize = 32 + 8 + 8;
+}
+
+/// One `record_reserve` attestation, USD amounts with 6 decimals.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ReserveSnapshot {
+    pub timestamp: i64,
+    pub total_supply: u64,
+    pub circulating_supply: u64,
+    pub liability_usd: u64,
+    pub reserve_usd: u64,
+    pub reserve_ratio_bps: u64,
+    pub caller: Pubkey,
+    pub assets: Vec<ReserveAsset>,
+}
+
+impl ReserveSnapshot {
+    pub const LEN: usize = 8 + // timestamp
+        8 + // total_supply
+        8 + // circulating_supply
+        8 + // liability_usd
+        8 + // reserve_usd
+        8 + // reserve_ratio_bps
+        32 + // caller
+        4 + MAX_RESERVE_VAULTS * ReserveAsset::LEN; // assets
+}
+
+/// Last `RESERVE_HISTORY_LEN` reserve attestations, seeded by `RESERVE_HISTORY_SEED`.
+#[account]
+pub struct ReserveHistory {
+    pub snapshots: Vec<ReserveSnapshot>, // Ring buffer, oldest overwritten first
+    pub next_index: u16,                 // 2 bytes, slot of the next snapshot
+    pub last_recorded_at: i64,           // 8 bytes
+    pub bump: u8,                        // 1 byte
+}
+
+impl ReserveHistory {
+    pub const LEN: usize = 8 + // discriminator
+        4 + RESERVE_HISTORY_LEN * ReserveSnapshot::LEN + // snapshots
+        2 + // next_index
+        8 + // last_recorded_at
+        1; // bump
+
+    /// Stores `snapshot`, overwriting the oldest one once the buffer is full.
+    /// Fails if the previous snapshot is younger than the minimum interval.
+    pub fn record(&mut self, snapshot: ReserveSnapshot) -> Result<()> {
+        require!(
+            self.snapshots.is_empty()
+                || snapshot.timestamp
+                    >= self
+                        .last_recorded_at
+                        .saturating_add(MIN_RESERVE_ATTESTATION_INTERVAL),
+            DiamondTokenError::ReserveAttestationTooSoon
+        );
+
+        let index = self.next_index as usize;
+        self.last_recorded_at = snapshot.timestamp;
+        if index < self.snapshots.len() {
+            self.snapshots[index] = snapshot;
+        } else {
+            self.snapshots.push(snapshot);
+        }
+        self.next_index = ((index + 1) % RESERVE_HISTORY_LEN) as u16;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reduction(timestamp: i64, max_supply: u64) -> ScheduledReduction {
+        ScheduledReduction {
+            timestamp,
+            max_supply,
+        }
+    }
+
+    fn config_with_tiers(volume_tiers: Vec<VolumeTier>) -> Config {
+        Config {
+            swap_program: Pubkey::default(),
+            usdt_reserve: Pubkey::default(),
+            buy_fee_bps: 0,
+            redeem_fee_bps: 0,
+            purchase_fee_bps: 0,
+            volume_tiers,
+            auto_freeze_on_blacklist: false,
+            compliance_mode: ComplianceMode::DenyList,
+            kyc_attester: Pubkey::default(),
+            kyc_tier_limits: [TierLimits::UNLIMITED; KYC_TIER_COUNT],
+            compliance_signer: Pubkey::default(),
+            require_geo_attestation: false,
+            denied_jurisdictions: Vec::new(),
+            pending_interest_rate: None,
+            reserve_vaults: Vec::new(),
+            kyc_exempt_owners: Vec::new(),
+            bump: 0,
+        }
+    }
+
+    fn tier(min_amount: u64, discount_bps: u16) -> VolumeTier {
+        VolumeTier {
+            min_amount,
+            discount_bps,
+        }
+    }
+
+    fn empty_schedule() -> MaxSupplySchedule {
+        MaxSupplySchedule {
+            reductions: Vec::new(),
+            next_index: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn bps_of_rounds_down() {
+        assert_eq!(Config::bps_of(1_000_000, 300).unwrap(), 30_000);
+        assert_eq!(Config::bps_of(333, 100).unwrap(), 3);
+        assert_eq!(Config::bps_of(99, 100).unwrap(), 0);
+        assert_eq!(Config::bps_of(u64::MAX, 0).unwrap(), 0);
+        assert_eq!(
+            Config::bps_of(u64::MAX, BPS_DENOMINATOR as u16).unwrap(),
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn volume_discount_uses_highest_tier_reached() {
+        let config = config_with_tiers(vec![tier(1_000, 100), tier(5_000, 250)]);
+        assert_eq!(config.volume_discount_bps(999), 0);
+        assert_eq!(config.volume_discount_bps(1_000), 100);
+        assert_eq!(config.volume_discount_bps(4_999), 100);
+        assert_eq!(config.volume_discount_bps(5_000), 250);
+        assert_eq!(config.volume_discount_bps(u64::MAX), 250);
+        assert_eq!(config_with_tiers(Vec::new()).volume_discount_bps(5_000), 0);
+    }
+
+    #[test]
+    fn volume_tiers_must_ascend_and_stay_capped() {
+        assert!(Config::validate_volume_tiers(&[tier(1_000, 100), tier(5_000, 100)]).is_ok());
+        assert!(Config::validate_volume_tiers(&[tier(5_000, 100), tier(1_000, 250)]).is_err());
+        assert!(Config::validate_volume_tiers(&[tier(1_000, 250), tier(5_000, 100)]).is_err());
+        assert!(Config::validate_volume_tiers(&[tier(0, 100)]).is_err());
+        assert!(
+            Config::validate_volume_tiers(&[tier(1_000, MAX_VOLUME_DISCOUNT_BPS + 1)]).is_err()
+        );
+        let too_many: Vec<_> = (1..=MAX_VOLUME_TIERS as u64 + 1)
+            .map(|i| tier(i * 1_000, 0))
+            .collect();
+        assert!(Config::validate_volume_tiers(&too_many).is_err());
+    }
+
+    fn volume() -> UserVolume {
+        UserVolume {
+            wallet: Pubkey::default(),
+            day: 0,
+            daily_amount: 0,
+            lifetime_amount: 0,
+            last_geo_nonce: 0,
+            bump: 0,
+        }
+    }
+
+    const LIMITS: TierLimits = TierLimits {
+        daily_limit: 100,
+        lifetime_limit: 250,
+    };
+
+    #[test]
+    fn user_volume_enforces_daily_limit() {
+        let mut volume = volume();
+        let now = 10 * SECONDS_PER_DAY;
+        volume.record(60, &LIMITS, now).unwrap();
+        volume.record(40, &LIMITS, now + 1).unwrap();
+        assert_eq!(
+            volume.record(1, &LIMITS, now + 2).unwrap_err(),
+            DiamondTokenError::KycLimitExceeded.into()
+        );
+        // A failed mint leaves the counters untouched
+        assert_eq!(volume.daily_amount, 100);
+        assert_eq!(volume.lifetime_amount, 100);
+    }
+
+    #[test]
+    fn user_volume_resets_daily_counter_on_a_new_day() {
+        let mut volume = volume();
+        let now = 10 * SECONDS_PER_DAY;
+        volume
+            .record(100, &LIMITS, now + SECONDS_PER_DAY - 1)
+            .unwrap();
+        volume.record(100, &LIMITS, now + SECONDS_PER_DAY).unwrap();
+        assert_eq!(volume.daily_amount, 100);
+        assert_eq!(volume.lifetime_amount, 200);
+    }
+
+    #[test]
+    fn user_volume_enforces_lifetime_limit() {
+        let mut volume = volume();
+        volume.record(100, &LIMITS, 0).unwrap();
+        volume.record(100, &LIMITS, SECONDS_PER_DAY).unwrap();
+        assert_eq!(
+            volume
+                .record(100, &LIMITS, 2 * SECONDS_PER_DAY)
+                .unwrap_err(),
+            DiamondTokenError::KycLimitExceeded.into()
+        );
+        volume.record(50, &LIMITS, 2 * SECONDS_PER_DAY).unwrap();
+        assert!(volume
+            .record(u64::MAX, &TierLimits::UNLIMITED, 2 * SECONDS_PER_DAY)
+            .is_err());
+    }
+
+    #[test]
+    fn append_requires_increasing_timestamps_and_decreasing_targets() {
+        let mut schedule = empty_schedule();
+        schedule
+            .append(&[reduction(200, 900), reduction(300, 800)], 1_000, 100)
+            .unwrap();
+        assert_eq!(schedule.reductions.len(), 2);
+
+        assert!(schedule.append(&[reduction(300, 700)], 1_000, 100).is_err());
+        assert!(schedule.append(&[reduction(400, 800)], 1_000, 100).is_err());
+        assert!(schedule.append(&[], 1_000, 100).is_err());
+        assert_eq!(schedule.reductions.len(), 2);
+    }
+
+    #[test]
+    fn append_rejects_past_timestamps() {
+        let mut schedule = empty_schedule();
+        assert!(schedule.append(&[reduction(100, 900)], 1_000, 100).is_err());
+
+        // Once the last reduction is in the past, new ones must still be in the future
+        schedule.append(&[reduction(200, 900)], 1_000, 100).unwrap();
+        assert!(schedule.append(&[reduction(250, 800)], 1_000, 500).is_err());
+        schedule.append(&[reduction(501, 800)], 1_000, 500).unwrap();
+    }
+
+    #[test]
+    fn append_caps_targets_at_current_max_supply() {
+        let mut schedule = empty_schedule();
+        schedule.append(&[reduction(200, 900)], 1_000, 100).unwrap();
+        // update_max_supply lowered the cap below the last scheduled target
+        assert!(schedule.append(&[reduction(300, 850)], 800, 100).is_err());
+        schedule.append(&[reduction(300, 700)], 800, 100).unwrap();
+    }
+
+    #[test]
+    fn append_is_bounded() {
+        let mut schedule = empty_schedule();
+        let reductions: Vec<_> = (0..=MAX_SCHEDULED_REDUCTIONS as u64)
+            .map(|i| reduction(200 + i as i64, 1_000 - i - 1))
+            .collect();
+        assert!(schedule.append(&reductions, 1_000, 100).is_err());
+        schedule
+            .append(&reductions[..MAX_SCHEDULED_REDUCTIONS], 1_000, 100)
+            .unwrap();
+    }
+
+    #[test]
+    fn append_frees_applied_reductions() {
+        let mut schedule = empty_schedule();
+        let reductions: Vec<_> = (0..MAX_SCHEDULED_REDUCTIONS as u64)
+            .map(|i| reduction(200 + i as i64, 1_000 - i - 1))
+            .collect();
+        schedule.append(&reductions, 1_000, 100).unwrap();
+        assert!(schedule.append(&[reduction(1_000, 100)], 900, 100).is_err());
+
+        // Once two are applied, two more fit, still below the last target
+        schedule.next_index = 2;
+        let last = *reductions.last().unwrap();
+        let more = [
+            reduction(1_000, last.max_supply - 1),
+            reduction(1_001, last.max_supply - 2),
+        ];
+        schedule.append(&more, 998, 202).unwrap();
+        assert_eq!(schedule.next_index, 0);
+        assert_eq!(schedule.reductions.len(), MAX_SCHEDULED_REDUCTIONS);
+        assert_eq!(schedule.reductions[0].timestamp, 202);
+        assert!(schedule
+            .append(&[reduction(1_002, last.max_supply - 3)], 998, 202)
+            .is_err());
+    }
+
+    fn snapshot(timestamp: i64) -> ReserveSnapshot {
+        ReserveSnapshot {
+            timestamp,
+            total_supply: 0,
+            circulating_supply: 0,
+            liability_usd: 0,
+            reserve_usd: 0,
+            reserve_ratio_bps: u64::MAX,
+            caller: Pubkey::default(),
+            assets: Vec::new(),
+        }
+    }
+
+    fn empty_history() -> ReserveHistory {
+        ReserveHistory {
+            snapshots: Vec::new(),
+            next_index: 0,
+            last_recorded_at: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn record_enforces_minimum_interval() {
+        let mut history = empty_history();
+        // The first snapshot needs no interval, even at time zero
+        history.record(snapshot(0)).unwrap();
+        assert_eq!(
+            history
+                .record(snapshot(MIN_RESERVE_ATTESTATION_INTERVAL - 1))
+                .unwrap_err(),
+            DiamondTokenError::ReserveAttestationTooSoon.into()
+        );
+        history
+            .record(snapshot(MIN_RESERVE_ATTESTATION_INTERVAL))
+            .unwrap();
+        assert_eq!(history.last_recorded_at, MIN_RESERVE_ATTESTATION_INTERVAL);
+        assert_eq!(history.snapshots.len(), 2);
+    }
+
+    #[test]
+    fn record_overwrites_oldest_once_full() {
+        let mut history = empty_history();
+        let at = |i: usize| i as i64 * MIN_RESERVE_ATTESTATION_INTERVAL;
+        for i in 0..RESERVE_HISTORY_LEN {
+            history.record(snapshot(at(i))).unwrap();
+        }
+        assert_eq!(history.snapshots.len(), RESERVE_HISTORY_LEN);
+        assert_eq!(history.next_index, 0);
+
+        history.record(snapshot(at(RESERVE_HISTORY_LEN))).unwrap();
+        assert_eq!(history.snapshots.len(), RESERVE_HISTORY_LEN);
+        assert_eq!(history.next_index, 1);
+        assert_eq!(history.snapshots[0].timestamp, at(RESERVE_HISTORY_LEN));
+        assert_eq!(history.snapshots[1].timestamp, at(1));
+    }
+
+    fn freeze_record(governance: bool) -> FreezeRecord {
+        FreezeRecord {
+            token_account: Pubkey::default(),
+            governance,
+            blacklist: false,
+            seized_amount: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn blacklist_hold_outlives_governance_freeze() {
+        // freeze_account, then a blacklisting while the account is frozen
+        let mut record = freeze_record(true);
+        assert!(record.hold_for_blacklist(true));
+        assert!(record.blacklist);
+
+        // thaw_account lifts the governance hold only
+        record.governance = false;
+        assert!(record.is_held());
+
+        // Removing the blacklisting releases it
+        record.blacklist = false;
+        assert!(!record.is_held());
+    }
+
+    #[test]
+    fn blacklist_hold_skips_unrecorded_freezes() {
+        let mut record = freeze_record(false);
+        assert!(!record.hold_for_blacklist(true));
+        assert!(!record.blacklist);
+
+        assert!(record.hold_for_blacklist(false));
+        assert!(record.blacklist);
+    }
+}
@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+};
+
+use crate::error::DiamondTokenError;
+
+/// Anchor discriminator of `swap(amount_in: u64, minimum_amount_out: u64)`.
+pub const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+
+/// Calls `swap` on an external program implementing the buyback interface.
+///
+/// Fixed accounts are `source`, `destination`, `authority` (signer) and
+/// `token_program`, the program of the source mint; pool-specific accounts
+/// follow in `pool_accounts` as-is.
+#[allow(clippy::too_many_arguments)]
+pub fn swap<'info>(
+    swap_program: &AccountInfo<'info>,
+    source: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    pool_accounts: &[AccountInfo<'info>],
+    amount_in: u64,
+    minimum_amount_out: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut accounts = vec![
+        AccountMeta::new(source.key(), false),
+        AccountMeta::new(destination.key(), false),
+        AccountMeta::new_readonly(authority.key(), true),
+        AccountMeta::new_readonly(token_program.key(), false),
+    ];
+    accounts.extend(pool_accounts.iter().map(|account| {
+        if account.is_writable {
+            AccountMeta::new(account.key(), account.is_signer)
+        } else {
+            AccountMeta::new_readonly(account.key(), account.is_signer)
+        }
+    }));
+
+    let mut data = SWAP_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+
+    let instruction = Instruction {
+        program_id: swap_program.key(),
+        accounts,
+        data,
+    };
+
+    let mut account_infos = vec![
+        source.clone(),
+        destination.clone(),
+        authority.clone(),
+        token_program.clone(),
+    ];
+    account_infos.extend(pool_accounts.iter().cloned());
+
+    invoke_signed(&instruction, &account_infos, signer_seeds)?;
+
+    Ok(())
+}
+
+/// Checks a completed swap against its request: the program took no more than
+/// `amount_in` and paid out something, and at least `minimum_amount_out`.
+pub fn check_swap_amounts(
+    amount_in: u64,
+    spent: u64,
+    received: u64,
+    minimum_amount_out: u64,
+) -> Result<()> {
+    require!(spent <= amount_in, DiamondTokenError::InvalidSwapProgram);
+    require!(
+        received > 0 && received >= minimum_amount_out,
+        DiamondTokenError::SlippageExceeded
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swap_may_not_overspend() {
+        assert!(check_swap_amounts(100, 100, 50, 50).is_ok());
+        assert!(check_swap_amounts(100, 40, 50, 50).is_ok());
+        assert_eq!(
+            check_swap_amounts(100, 101, 50, 50).unwrap_err(),
+            DiamondTokenError::InvalidSwapProgram.into()
+        );
+    }
+
+    #[test]
+    fn swap_output_must_meet_minimum() {
+        assert_eq!(
+            check_swap_amounts(100, 100, 49, 50).unwrap_err(),
+            DiamondTokenError::SlippageExceeded.into()
+        );
+        // A zero minimum still requires some output
+        assert_eq!(
+            check_swap_amounts(100, 100, 0, 0).unwrap_err(),
+            DiamondTokenError::SlippageExceeded.into()
+        );
+    }
+}
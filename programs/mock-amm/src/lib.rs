@@ -0,0 +1,195 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::onchain::invoke_transfer_checked;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+declare_id!("CzuYLSyetcY77fnGbzEiYq6wJCSX3gWEyh7fFY6H916k");
+
+pub const POOL_SEED: &[u8] = b"pool";
+
+/// Fixed-rate pool implementing the swap interface `diamond::buyback_and_burn` calls.
+#[program]
+pub mod mock_amm {
+    use super::*;
+
+    pub fn initialize_pool(
+        ctx: Context<InitializePool>,
+        rate_numerator: u64,
+        rate_denominator: u64,
+    ) -> Result<()> {
+        require!(rate_denominator > 0, MockAmmError::InvalidRate);
+
+        let pool = &mut ctx.accounts.pool;
+        pool.input_vault = ctx.accounts.input_vault.key();
+        pool.output_vault = ctx.accounts.output_vault.key();
+        pool.rate_numerator = rate_numerator;
+        pool.rate_denominator = rate_denominator;
+        pool.bump = ctx.bumps.pool;
+
+        Ok(())
+    }
+
+    /// Remaining accounts are the transfer-hook accounts of either mint, when
+    /// it has a hook; they are forwarded to both transfers.
+    pub fn swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Swap<'info>>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        let pool = &ctx.accounts.pool;
+        let amount_out = pool.amount_out(amount_in, minimum_amount_out)?;
+
+        invoke_transfer_checked(
+            ctx.accounts.token_program.key,
+            ctx.accounts.source.to_account_info(),
+            ctx.accounts.input_mint.to_account_info(),
+            ctx.accounts.input_vault.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+            ctx.remaining_accounts,
+            amount_in,
+            ctx.accounts.input_mint.decimals,
+            &[],
+        )?;
+
+        let pool_seeds = &[POOL_SEED, &[pool.bump]];
+        let signer = &[&pool_seeds[..]];
+
+        invoke_transfer_checked(
+            ctx.accounts.output_token_program.key,
+            ctx.accounts.output_vault.to_account_info(),
+            ctx.accounts.output_mint.to_account_info(),
+            ctx.accounts.destination.to_account_info(),
+            ctx.accounts.pool.to_account_info(),
+            ctx.remaining_accounts,
+            amount_out,
+            ctx.accounts.output_mint.decimals,
+            signer,
+        )?;
+
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Pool {
+    pub input_vault: Pubkey,   // 32 bytes
+    pub output_vault: Pubkey,  // 32 bytes
+    pub rate_numerator: u64,   // 8 bytes
+    pub rate_denominator: u64, // 8 bytes
+    pub bump: u8,              // 1 byte
+}
+
+impl Pool {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 1;
+
+    /// Output for `amount_in` at the pool rate, failing below `minimum_amount_out`.
+    pub fn amount_out(&self, amount_in: u64, minimum_amount_out: u64) -> Result<u64> {
+        let amount_out = (amount_in as u128)
+            .checked_mul(self.rate_numerator as u128)
+            .and_then(|value| value.checked_div(self.rate_denominator as u128))
+            .and_then(|value| u64::try_from(value).ok())
+            .ok_or(MockAmmError::MathOverflow)?;
+
+        require!(
+            amount_out >= minimum_amount_out,
+            MockAmmError::SlippageExceeded
+        );
+
+        Ok(amount_out)
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(init, payer = payer, space = Pool::LEN, seeds = [POOL_SEED], bump)]
+    pub pool: Account<'info, Pool>,
+
+    #[account(token::authority = pool)]
+    pub input_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(token::authority = pool)]
+    pub output_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Account order is the interface: source, destination, authority, token_program
+// (of the input mint), then the pool-specific accounts.
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    #[account(mut)]
+    pub source: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(seeds = [POOL_SEED], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut, address = pool.input_vault)]
+    pub input_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = pool.output_vault)]
+    pub output_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = input_vault.mint)]
+    pub input_mint: InterfaceAccount<'info, Mint>,
+    #[account(address = output_vault.mint)]
+    pub output_mint: InterfaceAccount<'info, Mint>,
+    /// Program of the output mint, which need not match the input mint's
+    pub output_token_program: Interface<'info, TokenInterface>,
+}
+
+#[error_code]
+pub enum MockAmmError {
+    #[msg("Invalid rate")]
+    InvalidRate,
+
+    #[msg("Math operation overflow")]
+    MathOverflow,
+
+    #[msg("Swap output below minimum")]
+    SlippageExceeded,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(rate_numerator: u64, rate_denominator: u64) -> Pool {
+        Pool {
+            input_vault: Pubkey::default(),
+            output_vault: Pubkey::default(),
+            rate_numerator,
+            rate_denominator,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn amount_out_applies_rate() {
+        // 1 USDT (6 decimals) buys 1.25 DIAMOND (9 decimals)
+        assert_eq!(
+            pool(1_250, 1).amount_out(1_000_000, 0).unwrap(),
+            1_250_000_000
+        );
+        assert_eq!(pool(1, 3).amount_out(10, 0).unwrap(), 3);
+    }
+
+    #[test]
+    fn amount_out_enforces_minimum() {
+        assert_eq!(
+            pool(1, 2).amount_out(100, 51).unwrap_err(),
+            MockAmmError::SlippageExceeded.into()
+        );
+        assert_eq!(pool(1, 2).amount_out(100, 50).unwrap(), 50);
+    }
+
+    #[test]
+    fn amount_out_rejects_overflow() {
+        assert_eq!(
+            pool(u64::MAX, 1).amount_out(2, 0).unwrap_err(),
+            MockAmmError::MathOverflow.into()
+        );
+    }
+}
@@ -1,9 +1,35 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Diamond } from "../target/types/diamond";
-import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY, Keypair } from '@solana/web3.js';
-import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, createMint, createAssociatedTokenAccount, mintTo } from '@solana/spl-token';
+import { DiamondTransferHook } from "../target/types/diamond_transfer_hook";
+import { MockAmm } from "../target/types/mock_amm";
+import {
+  PublicKey,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  Keypair,
+  Transaction,
+  TransactionInstruction,
+  Ed25519Program,
+} from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  ExtensionType,
+  createAssociatedTokenAccount,
+  createInitializeMintInstruction,
+  createInitializeInterestBearingMintInstruction,
+  createInitializePermanentDelegateInstruction,
+  createInitializeTransferHookInstruction,
+  createTransferCheckedWithTransferHookInstruction,
+  getAssociatedTokenAddressSync,
+  getMintLen,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from '@solana/spl-token';
 import { assert } from "chai";
+import * as fs from "fs";
 
 describe("diamond", () => {
   // Configure the client to use the local cluster
@@ -11,16 +37,20 @@ describe("diamond", () => {
   anchor.setProvider(provider);
 
   const program = anchor.workspace.Diamond as Program<Diamond>;
+  const hookProgram = anchor.workspace.DiamondTransferHook as Program<DiamondTransferHook>;
+  const ammProgram = anchor.workspace.MockAmm as Program<MockAmm>;
   
   // Test accounts
   let tokenState: PublicKey;
-  let blacklist: PublicKey;
+  let config: PublicKey;
   let mint: PublicKey;
   let vault: PublicKey;
   let multisig: Keypair;
+  let user: Keypair;
+  let extraAccountMetaList: PublicKey;
   let userTokenAccount: PublicKey;
   let userPaymentAccount: PublicKey;
-  let mockPythPriceFeed: Keypair;
+  let usdtVault: PublicKey;
 
   // Test constants
   const INITIAL_SUPPLY = new anchor.BN("8000000000000000"); // 8_000_000 * 10^9
@@ -28,54 +58,176 @@ describe("diamond", () => {
   const TOKEN_PRICE_USDT = new anchor.BN("1000000"); // 1_000_000
   const TOKEN_PRICE_USDC = new anchor.BN("800000"); // 800_000
 
-  before(async () => {
-    // Create mock Pyth price feed account
-    mockPythPriceFeed = anchor.web3.Keypair.generate();
-    const rent = await provider.connection.getMinimumBalanceForRentExemption(3000);
-    const createAccountIx = SystemProgram.createAccount({
-      fromPubkey: provider.wallet.publicKey,
-      newAccountPubkey: mockPythPriceFeed.publicKey,
-      lamports: rent,
-      space: 3000,
-      programId: new PublicKey("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH")
-    });
+  // Loaded by Anchor.toml from tests/fixtures, minted by the fixture keypair
+  const USDT_MINT = new PublicKey("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB");
+  // Only read for wrapped SOL payments, so the account need not exist locally
+  const SOL_USD_PRICE_FEED = new PublicKey("H6ARHf6YXhGYeQfUzQNGk6rDNnLBQKrenN712K4AQJEG");
+  const usdtMintAuthority = Keypair.fromSecretKey(
+    Uint8Array.from(JSON.parse(fs.readFileSync("tests/fixtures/usdt-mint-authority.json", "utf8")))
+  );
 
-    const transaction = new anchor.web3.Transaction().add(createAccountIx);
-    
-    // Исправляем передачу signers
-    await provider.sendAndConfirm(
-      transaction,
-      [mockPythPriceFeed]  // Убираем provider.wallet.payer, так как он уже включен в provider
+  const pda = (...seeds: Buffer[]) =>
+    PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+  const blacklistEntryFor = (address: PublicKey) =>
+    pda(Buffer.from("blacklist_v2"), address.toBuffer());
+  const kycRecordFor = (address: PublicKey) =>
+    pda(Buffer.from("kyc"), address.toBuffer());
+  const userVolumeFor = (address: PublicKey) =>
+    pda(Buffer.from("user_volume"), address.toBuffer());
+  const feeTreasury = pda(Buffer.from("fee_treasury"));
+  const seizureEscrow = pda(Buffer.from("seizure_escrow"));
+
+  // Accounts Token-2022 needs to call the hook when the program moves DIAMOND
+  // out of `owner`'s account, one blacklist entry and KYC record per destination owner
+  const hookAccountsFor = (owner: PublicKey, destinationOwners: PublicKey[]) =>
+    [
+      extraAccountMetaList,
+      hookProgram.programId,
+      program.programId,
+      blacklistEntryFor(owner),
+      blacklistEntryFor(PublicKey.default),
+      config,
+      tokenState,
+      ...destinationOwners.flatMap((destinationOwner) => [
+        blacklistEntryFor(destinationOwner),
+        kycRecordFor(destinationOwner),
+      ]),
+    ].map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }));
+
+  const airdrop = async (address: PublicKey) => {
+    const signature = await provider.connection.requestAirdrop(address, anchor.web3.LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(signature);
+  };
+
+  const errorCode = (error: any) => error.error?.errorCode?.code;
+
+  // A wallet holding 1000 USDT and an empty DIAMOND account
+  const newBuyer = async () => {
+    const buyer = Keypair.generate();
+    await airdrop(buyer.publicKey);
+    const paymentAccount = await createAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer,
+      USDT_MINT,
+      buyer.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      provider.wallet.payer,
+      USDT_MINT,
+      paymentAccount,
+      usdtMintAuthority,
+      1_000_000_000
     );
+    const tokenAccount = await createAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer,
+      mint,
+      buyer.publicKey,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    return { buyer, paymentAccount, tokenAccount };
+  };
+
+  const mintWithUsdt = (
+    buyer: { buyer: Keypair; paymentAccount: PublicKey; tokenAccount: PublicKey },
+    amount: anchor.BN,
+    preInstructions: TransactionInstruction[] = []
+  ) =>
+    program.methods
+      .mintByUser(amount)
+      .accounts({
+        user: buyer.buyer.publicKey,
+        tokenState,
+        mint,
+        paymentToken: USDT_MINT,
+        userPaymentAccount: buyer.paymentAccount,
+        userTokenAccount: buyer.tokenAccount,
+        vault: usdtVault,
+        userBlacklistEntry: blacklistEntryFor(buyer.buyer.publicKey),
+        userKycRecord: kycRecordFor(buyer.buyer.publicKey),
+        userVolume: userVolumeFor(buyer.buyer.publicKey),
+        config,
+        feeTreasury,
+        feeAccount: getAssociatedTokenAddressSync(USDT_MINT, feeTreasury, true),
+        solPriceFeed: null,
+        instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        paymentTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .preInstructions(preInstructions)
+      .signers([buyer.buyer])
+      .rpc();
 
+  const addToBlacklist = (address: PublicKey) =>
+    program.methods
+      .addToBlacklist(address, { internal: {} }, null, Array(32).fill(0))
+      .accounts({
+        authority: provider.wallet.publicKey,
+        tokenState,
+        blacklistEntry: blacklistEntryFor(address),
+        config,
+        mint,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  before(async () => {
     // Initialize test accounts
     [tokenState] = await PublicKey.findProgramAddress(
       [Buffer.from("token_state_v2")],
       program.programId
     );
 
-    [blacklist] = await PublicKey.findProgramAddress(
-      [Buffer.from("blacklist_v2")],
+    [config] = await PublicKey.findProgramAddress(
+      [Buffer.from("config")],
       program.programId
     );
 
-    // Create mint account
-    mint = await createMint(
-      provider.connection,
-      provider.wallet,
-      provider.wallet.publicKey,
-      null,
-      9
+    // Create a Token-2022 mint whose transfers go through the hook program,
+    // frozen accounts are managed by the token state PDA. The token state is
+    // also its rate authority and permanent delegate
+    const mintKeypair = Keypair.generate();
+    mint = mintKeypair.publicKey;
+    const mintLen = getMintLen([
+      ExtensionType.TransferHook,
+      ExtensionType.InterestBearingConfig,
+      ExtensionType.PermanentDelegate,
+    ]);
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: provider.wallet.publicKey,
+          newAccountPubkey: mint,
+          space: mintLen,
+          lamports: await provider.connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferHookInstruction(
+          mint,
+          provider.wallet.publicKey,
+          hookProgram.programId,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeInterestBearingMintInstruction(mint, tokenState, 0, TOKEN_2022_PROGRAM_ID),
+        createInitializePermanentDelegateInstruction(mint, tokenState, TOKEN_2022_PROGRAM_ID),
+        createInitializeMintInstruction(mint, 9, tokenState, tokenState, TOKEN_2022_PROGRAM_ID)
+      ),
+      [mintKeypair]
     );
-    
-    // Create vault account
-    vault = await createAssociatedTokenAccount(
-      provider.connection,
-      provider.wallet.payer,
-      mint,
-      provider.wallet.publicKey
+
+    [extraAccountMetaList] = PublicKey.findProgramAddressSync(
+      [Buffer.from("extra-account-metas"), mint.toBuffer()],
+      hookProgram.programId
     );
     
+    // Vault PDA, created by initialize
+    vault = pda(Buffer.from("vault_v2"));
+    
     // Create multisig account
     multisig = anchor.web3.Keypair.generate();
   });
@@ -100,11 +252,10 @@ describe("diamond", () => {
           payer: provider.wallet.publicKey,
           tokenState,
           mint,
-          blacklist,
           multisig: multisig.publicKey,
           vault,
           systemProgram: SystemProgram.programId,
-          tokenProgram: TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
           rent: SYSVAR_RENT_PUBKEY,
         })
         .signers([provider.wallet.payer, multisig, ...multisigKeypairs])
@@ -115,48 +266,107 @@ describe("diamond", () => {
       assert.ok(tokenStateAccount.totalSupply.eq(INITIAL_SUPPLY));
       assert.ok(tokenStateAccount.maxSupply.eq(MAX_SUPPLY));
       assert.ok(tokenStateAccount.isPaused === false);
+      assert.ok(tokenStateAccount.vault.equals(vault));
+
+      // The initial supply lands in the vault PDA created by initialize
+      const vaultBalance = await provider.connection.getTokenAccountBalance(vault);
+      assert.ok(new anchor.BN(vaultBalance.value.amount).eq(INITIAL_SUPPLY));
     } catch (error) {
       console.error("Initialization error:", error);
       throw error;
     }
   });
 
+  it("Initializes the config", async () => {
+    await program.methods
+      .initializeConfig()
+      .accounts({
+        authority: provider.wallet.publicKey,
+        tokenState,
+        config,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const configAccount = await program.account.config.fetch(config);
+    assert.ok(configAccount.buyFeeBps === 0);
+    assert.ok(configAccount.autoFreezeOnBlacklist === false);
+  });
+
+  it("Initializes the transfer hook accounts", async () => {
+    await hookProgram.methods
+      .initializeExtraAccountMetaList()
+      .accounts({
+        authority: provider.wallet.publicKey,
+        tokenState,
+        mint,
+        extraAccountMetaList,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const metaList = await provider.connection.getAccountInfo(extraAccountMetaList);
+    assert.ok(metaList.owner.equals(hookProgram.programId));
+  });
+
   it("Mints tokens with USDT payment", async () => {
     // Create test user
-    const user = anchor.web3.Keypair.generate();
-    
-    // Create mock USDT mint and accounts
-    const usdtMint = await createMint(
+    user = anchor.web3.Keypair.generate();
+    await airdrop(user.publicKey);
+
+    // The payment vault must be a registered reserve vault owned by the token state
+    usdtVault = (await getOrCreateAssociatedTokenAccount(
       provider.connection,
-      provider.wallet,
-      provider.wallet.publicKey,
-      null,
-      6
-    );
+      provider.wallet.payer,
+      USDT_MINT,
+      tokenState,
+      true
+    )).address;
+
+    await program.methods
+      .setReserveVaults()
+      .accounts({
+        authority: provider.wallet.publicKey,
+        tokenState,
+        config,
+      })
+      .remainingAccounts([{ pubkey: usdtVault, isSigner: false, isWritable: false }])
+      .rpc();
+
+    // Buy fees are paid into an account owned by the fee treasury PDA
+    const feeAccount = (await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer,
+      USDT_MINT,
+      feeTreasury,
+      true
+    )).address;
 
     // Create user USDT account
     userPaymentAccount = await createAssociatedTokenAccount(
       provider.connection,
-      provider.wallet,
-      usdtMint,
+      provider.wallet.payer,
+      USDT_MINT,
       user.publicKey
     );
 
     // Create user token account
     userTokenAccount = await createAssociatedTokenAccount(
       provider.connection,
-      provider.wallet,
+      provider.wallet.payer,
       mint,
-      user.publicKey
+      user.publicKey,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
     );
 
     // Mint some USDT to user
     await mintTo(
       provider.connection,
-      provider.wallet,
-      usdtMint,
+      provider.wallet.payer,
+      USDT_MINT,
       userPaymentAccount,
-      provider.wallet,
+      usdtMintAuthority,
       1000000000
     );
 
@@ -169,28 +379,257 @@ describe("diamond", () => {
           user: user.publicKey,
           tokenState,
           mint,
-          paymentToken: usdtMint,
+          paymentToken: USDT_MINT,
           userPaymentAccount,
           userTokenAccount,
-          vault,
-          blacklist,
-          solPriceFeed: mockPythPriceFeed.publicKey,
-          tokenProgram: TOKEN_PROGRAM_ID,
+          vault: usdtVault,
+          userBlacklistEntry: blacklistEntryFor(user.publicKey),
+          userKycRecord: kycRecordFor(user.publicKey),
+          userVolume: userVolumeFor(user.publicKey),
+          config,
+          feeTreasury,
+          feeAccount,
+          solPriceFeed: null, // Only needed for wrapped SOL payments
+          instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          paymentTokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
         .signers([user])
         .rpc();
 
-      // Verify mint
+      // Verify mint and payment
       const balance = await provider.connection.getTokenAccountBalance(userTokenAccount);
       assert.ok(new anchor.BN(balance.value.amount).eq(amount));
+      const vaultBalance = await provider.connection.getTokenAccountBalance(usdtVault);
+      assert.equal(vaultBalance.value.amount, "1000000000");
     } catch (error) {
       console.error("Mint error:", error);
       throw error;
     }
   });
 
+  it("Purchases an item through the transfer hook", async () => {
+    // A purchase fee makes the program issue a second hooked transfer
+    await program.methods
+      .setFees(0, 0, 100)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        tokenState,
+        config,
+      })
+      .rpc();
+
+    const feeAccount = (await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer,
+      mint,
+      feeTreasury,
+      true,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    )).address;
+
+    const vaultBefore = await provider.connection.getTokenAccountBalance(vault);
+    const amount = new anchor.BN("100000000000"); // 100 tokens
+
+    await program.methods
+      .purchaseItem(amount)
+      .accounts({
+        user: user.publicKey,
+        tokenState,
+        mint,
+        userTokenAccount,
+        userBlacklistEntry: blacklistEntryFor(user.publicKey),
+        userKycRecord: kycRecordFor(user.publicKey),
+        vault,
+        config,
+        feeTreasury,
+        feeAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(hookAccountsFor(user.publicKey, [tokenState, feeTreasury]))
+      .signers([user])
+      .rpc();
+
+    const vaultAfter = await provider.connection.getTokenAccountBalance(vault);
+    assert.ok(
+      new anchor.BN(vaultAfter.value.amount).sub(new anchor.BN(vaultBefore.value.amount)).eq(amount)
+    );
+    const feeBalance = await provider.connection.getTokenAccountBalance(feeAccount);
+    assert.equal(feeBalance.value.amount, "1000000000"); // 1% of 100 tokens
+  });
+
+  describe("buyback", () => {
+    const pool = PublicKey.findProgramAddressSync([Buffer.from("pool")], ammProgram.programId)[0];
+    const buybackAuthority = pda(Buffer.from("buyback_authority"));
+    let inputVault: PublicKey;
+    let outputVault: PublicKey;
+    let buybackAccount: PublicKey;
+    let feeAccount: PublicKey;
+
+    // Reserve vaults in config order, then the swap program's accounts and
+    // the hook accounts for the DIAMOND leg, pool to buyback account
+    const buybackRemainingAccounts = () => [
+      { pubkey: usdtVault, isSigner: false, isWritable: false },
+      { pubkey: pool, isSigner: false, isWritable: false },
+      { pubkey: inputVault, isSigner: false, isWritable: true },
+      { pubkey: outputVault, isSigner: false, isWritable: true },
+      { pubkey: USDT_MINT, isSigner: false, isWritable: false },
+      { pubkey: mint, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_2022_PROGRAM_ID, isSigner: false, isWritable: false },
+      ...hookAccountsFor(pool, [tokenState]),
+    ];
+
+    const buybackAndBurn = (usdtAmount: anchor.BN, minimumDiamondOut: anchor.BN) =>
+      program.methods
+        .buybackAndBurn(usdtAmount, minimumDiamondOut)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          tokenState,
+          config,
+          mint,
+          usdtReserve: usdtVault,
+          vault,
+          seizureEscrow: null,
+          feeTreasury,
+          // Purchase fees in DIAMOND, left out of the circulating supply
+          feeAccount,
+          solPriceFeed: null,
+          buybackAccount,
+          buybackAuthority,
+          swapProgram: ammProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          reserveTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(buybackRemainingAccounts())
+        .rpc();
+
+    before(async () => {
+      // USDT comes in on classic SPL, DIAMOND goes out on Token-2022
+      inputVault = (await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        provider.wallet.payer,
+        USDT_MINT,
+        pool,
+        true
+      )).address;
+      outputVault = (await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        provider.wallet.payer,
+        mint,
+        pool,
+        true,
+        undefined,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      )).address;
+      buybackAccount = (await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        provider.wallet.payer,
+        mint,
+        tokenState,
+        true,
+        undefined,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      )).address;
+      feeAccount = (await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        provider.wallet.payer,
+        mint,
+        feeTreasury,
+        true,
+        undefined,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      )).address;
+
+      // 1 USDT (6 decimals) buys 1 DIAMOND (9 decimals)
+      await ammProgram.methods
+        .initializePool(new anchor.BN(1000), new anchor.BN(1))
+        .accounts({
+          payer: provider.wallet.publicKey,
+          pool,
+          inputVault,
+          outputVault,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      // Stock the pool with 20 DIAMOND from the user, through the hook
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          await createTransferCheckedWithTransferHookInstruction(
+            provider.connection,
+            userTokenAccount,
+            mint,
+            outputVault,
+            user.publicKey,
+            BigInt(20_000_000_000),
+            9,
+            [],
+            undefined,
+            TOKEN_2022_PROGRAM_ID
+          )
+        ),
+        [user]
+      );
+
+      await program.methods
+        .setBuybackConfig(ammProgram.programId)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          tokenState,
+          config,
+          usdtReserve: usdtVault,
+        })
+        .rpc();
+    });
+
+    it("Buys DIAMOND back with reserve USDT and burns it", async () => {
+      const supplyBefore = (await program.account.tokenState.fetch(tokenState)).totalSupply;
+      const reserveBefore = await provider.connection.getTokenAccountBalance(usdtVault);
+      const usdtAmount = new anchor.BN(10_000_000); // 10 USDT
+      const diamondOut = new anchor.BN("10000000000"); // 10 tokens
+
+      await buybackAndBurn(usdtAmount, diamondOut);
+
+      const supplyAfter = (await program.account.tokenState.fetch(tokenState)).totalSupply;
+      assert.ok(supplyBefore.sub(supplyAfter).eq(diamondOut));
+      const reserveAfter = await provider.connection.getTokenAccountBalance(usdtVault);
+      assert.ok(
+        new anchor.BN(reserveBefore.value.amount).sub(new anchor.BN(reserveAfter.value.amount)).eq(usdtAmount)
+      );
+      const buybackBalance = await provider.connection.getTokenAccountBalance(buybackAccount);
+      assert.equal(buybackBalance.value.amount, "0");
+      const inputBalance = await provider.connection.getTokenAccountBalance(inputVault);
+      assert.ok(new anchor.BN(inputBalance.value.amount).eq(usdtAmount));
+    });
+
+    it("Refuses to spend more than the reserve surplus", async () => {
+      // The reserve holds about 1000 USDT against roughly 900 DIAMOND circulating
+      try {
+        await buybackAndBurn(new anchor.BN(500_000_000), new anchor.BN(0));
+        assert.fail("spent beyond the reserve surplus");
+      } catch (error) {
+        assert.equal(error.error?.errorCode?.code, "InsufficientReserveSurplus");
+      }
+    });
+
+    it("Refuses a swap that pays out less than the minimum", async () => {
+      try {
+        await buybackAndBurn(new anchor.BN(1_000_000), new anchor.BN("2000000000"));
+        assert.fail("accepted a swap below the minimum output");
+      } catch (error) {
+        // The mock pool enforces the minimum before paying out
+        assert.equal(error.error?.errorCode?.code, "SlippageExceeded");
+      }
+    });
+  });
+
   it("Pauses and unpauses token operations", async () => {
     try {
       // Pause
@@ -230,23 +669,26 @@ describe("diamond", () => {
 
   it("Updates blacklist", async () => {
     const addressToBlacklist = anchor.web3.Keypair.generate().publicKey;
+    const blacklistEntry = blacklistEntryFor(addressToBlacklist);
 
     try {
       // Add to blacklist
       await program.methods
-        .addToBlacklist(addressToBlacklist)
+        .addToBlacklist(addressToBlacklist, { internal: {} }, null, Array(32).fill(0))
         .accounts({
           authority: provider.wallet.publicKey,
           tokenState,
-          blacklist,
-          multisig: multisig.publicKey,
+          blacklistEntry,
+          config,
+          mint,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
         })
-        .signers([provider.wallet.payer, multisig])
         .rpc();
 
       // Verify blacklist
-      let blacklistAccount = await program.account.blacklist.fetch(blacklist);
-      assert.ok(blacklistAccount.addresses.some(addr => addr.equals(addressToBlacklist)));
+      const entryAccount = await program.account.blacklistEntry.fetch(blacklistEntry);
+      assert.ok(entryAccount.address.equals(addressToBlacklist));
 
       // Remove from blacklist
       await program.methods
@@ -254,18 +696,538 @@ describe("diamond", () => {
         .accounts({
           authority: provider.wallet.publicKey,
           tokenState,
-          blacklist,
-          multisig: multisig.publicKey,
+          blacklistEntry,
+          config,
+          mint,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
         })
-        .signers([provider.wallet.payer, multisig])
         .rpc();
 
       // Verify removal
-      blacklistAccount = await program.account.blacklist.fetch(blacklist);
-      assert.ok(!blacklistAccount.addresses.some(addr => addr.equals(addressToBlacklist)));
+      const closedAccount = await provider.connection.getAccountInfo(blacklistEntry);
+      assert.ok(closedAccount === null);
     } catch (error) {
       console.error("Blacklist update error:", error);
       throw error;
     }
   });
-});
\ No newline at end of file
+
+  it("Refuses to approve confidential transfers for a blacklisted owner", async () => {
+    const owner = anchor.web3.Keypair.generate().publicKey;
+    const tokenAccount = await createAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer,
+      mint,
+      owner,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const blacklistEntry = blacklistEntryFor(owner);
+
+    await program.methods
+      .addToBlacklist(owner, { internal: {} }, null, Array(32).fill(0))
+      .accounts({
+        authority: provider.wallet.publicKey,
+        tokenState,
+        blacklistEntry,
+        config,
+        mint,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    try {
+      await program.methods
+        .approveConfidentialAccount()
+        .accounts({
+          authority: provider.wallet.publicKey,
+          tokenState,
+          mint,
+          tokenAccount,
+          ownerKycRecord: kycRecordFor(owner),
+          ownerBlacklistEntry: blacklistEntry,
+          config,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("approved a blacklisted owner");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "AddressBlacklisted");
+    }
+  });
+
+  describe("batch blacklist", () => {
+    const caseReference = Array(32).fill(7);
+    const entries = (addresses: PublicKey[]) =>
+      addresses.map((address) => ({ pubkey: blacklistEntryFor(address), isSigner: false, isWritable: true }));
+    const batchAccounts = {
+      authority: provider.wallet.publicKey,
+      tokenState,
+      config,
+      mint,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+
+    it("Adds and removes several addresses at once", async () => {
+      const addresses = [Keypair.generate().publicKey, Keypair.generate().publicKey];
+
+      await program.methods
+        .batchAddToBlacklist(addresses, { fraud: {} }, null, caseReference, { fail: {} })
+        .accounts(batchAccounts)
+        .remainingAccounts(entries(addresses))
+        .rpc();
+
+      for (const address of addresses) {
+        const entry = await program.account.blacklistEntry.fetch(blacklistEntryFor(address));
+        assert.ok(entry.address.equals(address));
+        assert.deepEqual(entry.caseReference, caseReference);
+      }
+
+      await program.methods
+        .batchRemoveFromBlacklist(addresses, { fail: {} })
+        .accounts(batchAccounts)
+        .remainingAccounts(entries(addresses))
+        .rpc();
+
+      for (const address of addresses) {
+        assert.isNull(await provider.connection.getAccountInfo(blacklistEntryFor(address)));
+      }
+    });
+
+    it("Fails on an address already listed unless told to skip it", async () => {
+      const listed = Keypair.generate().publicKey;
+      const fresh = Keypair.generate().publicKey;
+      await addToBlacklist(listed);
+
+      try {
+        await program.methods
+          .batchAddToBlacklist([fresh, listed], { fraud: {} }, null, caseReference, { fail: {} })
+          .accounts(batchAccounts)
+          .remainingAccounts(entries([fresh, listed]))
+          .rpc();
+        assert.fail("re-listed an active entry");
+      } catch (error) {
+        assert.equal(errorCode(error), "AddressAlreadyBlacklisted");
+      }
+      // The whole batch rolled back
+      assert.isNull(await provider.connection.getAccountInfo(blacklistEntryFor(fresh)));
+
+      await program.methods
+        .batchAddToBlacklist([fresh, listed], { fraud: {} }, null, caseReference, { skip: {} })
+        .accounts(batchAccounts)
+        .remainingAccounts(entries([fresh, listed]))
+        .rpc();
+      const entry = await program.account.blacklistEntry.fetch(blacklistEntryFor(fresh));
+      assert.ok(entry.address.equals(fresh));
+    });
+  });
+
+  describe("KYC tiers and limits", () => {
+    const tokens = (count: number) => new anchor.BN(count).mul(new anchor.BN(1_000_000_000));
+    const unlimited = {
+      dailyLimit: new anchor.BN("18446744073709551615"),
+      lifetimeLimit: new anchor.BN("18446744073709551615"),
+    };
+    const setLimits = (limits: { dailyLimit: anchor.BN; lifetimeLimit: anchor.BN }[]) =>
+      program.methods
+        .setKycTierLimits(limits)
+        .accounts({ authority: provider.wallet.publicKey, tokenState, config })
+        .rpc();
+
+    before(async () => {
+      await program.methods
+        .setKycAttester(provider.wallet.publicKey)
+        .accounts({ authority: provider.wallet.publicKey, tokenState, config })
+        .rpc();
+
+      // Unverified wallets may not buy, Basic up to 50 a day, Enhanced without limit
+      await setLimits([
+        { dailyLimit: new anchor.BN(0), lifetimeLimit: new anchor.BN(0) },
+        { dailyLimit: tokens(50), lifetimeLimit: tokens(100) },
+        unlimited,
+      ]);
+    });
+
+    after(async () => {
+      await setLimits([unlimited, unlimited, unlimited]);
+    });
+
+    it("Lets a Basic wallet buy up to its daily limit", async () => {
+      const buyer = await newBuyer();
+      await program.methods
+        .issueKycRecord(
+          buyer.buyer.publicKey,
+          { basic: {} },
+          new anchor.BN(Math.floor(Date.now() / 1000) + 86_400)
+        )
+        .accounts({
+          attester: provider.wallet.publicKey,
+          config,
+          kycRecord: kycRecordFor(buyer.buyer.publicKey),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      await mintWithUsdt(buyer, tokens(50));
+      const volume = await program.account.userVolume.fetch(userVolumeFor(buyer.buyer.publicKey));
+      assert.ok(volume.dailyAmount.eq(tokens(50)));
+
+      try {
+        await mintWithUsdt(buyer, tokens(1));
+        assert.fail("minted past the daily limit");
+      } catch (error) {
+        assert.equal(errorCode(error), "KycLimitExceeded");
+      }
+    });
+
+    it("Refuses wallets without a KYC record under a zero limit", async () => {
+      const buyer = await newBuyer();
+      try {
+        await mintWithUsdt(buyer, tokens(1));
+        assert.fail("an unverified wallet minted");
+      } catch (error) {
+        assert.equal(errorCode(error), "KycLimitExceeded");
+      }
+    });
+
+    it("Refuses a daily limit above the lifetime limit", async () => {
+      try {
+        await setLimits([
+          unlimited,
+          { dailyLimit: tokens(2), lifetimeLimit: tokens(1) },
+          unlimited,
+        ]);
+        assert.fail("accepted inconsistent limits");
+      } catch (error) {
+        assert.equal(errorCode(error), "InvalidKycLimits");
+      }
+    });
+  });
+
+  describe("geo attestation", () => {
+    const complianceSigner = Keypair.generate();
+    const amount = new anchor.BN("1000000000"); // 1 token
+
+    // Ed25519 verify instruction carrying a signed geo attestation for `user`
+    const attestation = (
+      user: PublicKey,
+      jurisdiction: string,
+      nonce: number,
+      signer: Keypair = complianceSigner
+    ) => {
+      const expiresAt = Buffer.alloc(8);
+      expiresAt.writeBigInt64LE(BigInt(Math.floor(Date.now() / 1000) + 600));
+      const nonceBytes = Buffer.alloc(8);
+      nonceBytes.writeBigUInt64LE(BigInt(nonce));
+      const message = Buffer.concat([
+        Buffer.from("DIAMOND_GEO_V1"),
+        program.programId.toBuffer(),
+        user.toBuffer(),
+        Buffer.from(jurisdiction),
+        expiresAt,
+        nonceBytes,
+      ]);
+      return Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: signer.secretKey,
+        message,
+      });
+    };
+
+    before(async () => {
+      await program.methods
+        .setGeoAttestation(complianceSigner.publicKey, true)
+        .accounts({ authority: provider.wallet.publicKey, tokenState, config })
+        .rpc();
+      await program.methods
+        .setDeniedJurisdictions([Array.from(Buffer.from("KP"))])
+        .accounts({ authority: provider.wallet.publicKey, tokenState, config })
+        .rpc();
+    });
+
+    after(async () => {
+      await program.methods
+        .setGeoAttestation(PublicKey.default, false)
+        .accounts({ authority: provider.wallet.publicKey, tokenState, config })
+        .rpc();
+    });
+
+    it("Mints with a fresh attestation from an allowed jurisdiction", async () => {
+      const buyer = await newBuyer();
+      await mintWithUsdt(buyer, amount, [attestation(buyer.buyer.publicKey, "US", 1)]);
+
+      const balance = await provider.connection.getTokenAccountBalance(buyer.tokenAccount);
+      assert.ok(new anchor.BN(balance.value.amount).eq(amount));
+      const volume = await program.account.userVolume.fetch(userVolumeFor(buyer.buyer.publicKey));
+      assert.equal(volume.lastGeoNonce.toNumber(), 1);
+
+      // Each signature is good for one mint
+      try {
+        await mintWithUsdt(buyer, amount, [attestation(buyer.buyer.publicKey, "US", 1)]);
+        assert.fail("replayed an attestation");
+      } catch (error) {
+        assert.equal(errorCode(error), "GeoNonceReused");
+      }
+    });
+
+    it("Refuses denied jurisdictions, missing and foreign attestations", async () => {
+      const buyer = await newBuyer();
+      const user = buyer.buyer.publicKey;
+
+      const cases: [TransactionInstruction[], string][] = [
+        [[attestation(user, "KP", 1)], "JurisdictionDenied"],
+        [[], "InvalidGeoAttestation"],
+        [[attestation(user, "US", 1, Keypair.generate())], "InvalidGeoAttestation"],
+        [[attestation(Keypair.generate().publicKey, "US", 1)], "InvalidGeoAttestation"],
+      ];
+      for (const [preInstructions, code] of cases) {
+        try {
+          await mintWithUsdt(buyer, amount, preInstructions);
+          assert.fail(`minted without a valid attestation, expected ${code}`);
+        } catch (error) {
+          assert.equal(errorCode(error), code);
+        }
+      }
+    });
+  });
+
+  describe("interest rate timelock", () => {
+    const accounts = () => ({
+      authority: provider.wallet.publicKey,
+      tokenState,
+      mint,
+      config,
+    });
+
+    it("Proposes a rate with notice and cancels it", async () => {
+      const before = Math.floor(Date.now() / 1000);
+      await program.methods.proposeInterestRate(250).accounts(accounts()).rpc();
+
+      let configAccount = await program.account.config.fetch(config);
+      assert.equal(configAccount.pendingInterestRate.rateBps, 250);
+      // Two days of notice, give or take clock drift against the validator
+      assert.isAtLeast(configAccount.pendingInterestRate.executableAt.toNumber(), before + 2 * 86_400 - 60);
+
+      await program.methods.cancelInterestRate().accounts(accounts()).rpc();
+      configAccount = await program.account.config.fetch(config);
+      assert.isNull(configAccount.pendingInterestRate);
+    });
+
+    it("Refuses to apply a rate before the timelock ends", async () => {
+      await program.methods.proposeInterestRate(250).accounts(accounts()).rpc();
+
+      try {
+        await program.methods
+          .applyInterestRate()
+          .accounts({
+            cranker: provider.wallet.publicKey,
+            tokenState,
+            mint,
+            config,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+          })
+          .rpc();
+        assert.fail("applied a timelocked rate");
+      } catch (error) {
+        assert.equal(errorCode(error), "InterestRateTimelocked");
+      }
+
+      await program.methods.cancelInterestRate().accounts(accounts()).rpc();
+    });
+
+    it("Refuses rates out of bounds", async () => {
+      try {
+        await program.methods.proposeInterestRate(-1).accounts(accounts()).rpc();
+        assert.fail("accepted a negative rate");
+      } catch (error) {
+        assert.equal(errorCode(error), "InterestRateOutOfBounds");
+      }
+    });
+  });
+
+  describe("seize", () => {
+    const amount = new anchor.BN("5000000000"); // 5 tokens
+    let target: Awaited<ReturnType<typeof newBuyer>>;
+
+    const seizureRecordFor = (caseReference: number[], targetAccount: PublicKey) =>
+      pda(Buffer.from("seizure"), Buffer.from(caseReference), targetAccount.toBuffer());
+
+    const seize = (caseReference: number[], owner: PublicKey, targetAccount: PublicKey) =>
+      program.methods
+        .seize(caseReference, amount)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          tokenState,
+          mint,
+          targetAccount,
+          targetOwnerBlacklistEntry: blacklistEntryFor(owner),
+          seizureEscrow,
+          seizureRecord: seizureRecordFor(caseReference, targetAccount),
+          // The mint has the token state as permanent delegate
+          freezeRecord: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        // The permanent-delegate transfer into escrow goes through the hook
+        .remainingAccounts(hookAccountsFor(owner, [tokenState]))
+        .rpc();
+
+    before(async () => {
+      await program.methods
+        .initializeSeizureEscrow()
+        .accounts({
+          authority: provider.wallet.publicKey,
+          tokenState,
+          mint,
+          seizureEscrow,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      target = await newBuyer();
+      await mintWithUsdt(target, new anchor.BN("10000000000"));
+    });
+
+    it("Refuses to seize from a holder who is not blacklisted", async () => {
+      try {
+        await seize(Array(32).fill(1), target.buyer.publicKey, target.tokenAccount);
+        assert.fail("seized from a holder in good standing");
+      } catch (error) {
+        assert.equal(errorCode(error), "TargetNotBlacklisted");
+      }
+    });
+
+    it("Moves a blacklisted holder's tokens into escrow", async () => {
+      await addToBlacklist(target.buyer.publicKey);
+      const supplyBefore = (await program.account.tokenState.fetch(tokenState)).totalSupply;
+
+      const caseReference = Array(32).fill(2);
+      await seize(caseReference, target.buyer.publicKey, target.tokenAccount);
+
+      const escrowBalance = await provider.connection.getTokenAccountBalance(seizureEscrow);
+      assert.ok(new anchor.BN(escrowBalance.value.amount).eq(amount));
+      const targetBalance = await provider.connection.getTokenAccountBalance(target.tokenAccount);
+      assert.equal(targetBalance.value.amount, "5000000000");
+
+      const record = await program.account.seizureRecord.fetch(
+        seizureRecordFor(caseReference, target.tokenAccount)
+      );
+      assert.ok(record.amount.eq(amount));
+      assert.deepEqual(record.method, { permanentDelegate: {} });
+
+      // Moved, not re-minted
+      const tokenStateAccount = await program.account.tokenState.fetch(tokenState);
+      assert.ok(tokenStateAccount.totalSupply.eq(supplyBefore));
+      assert.ok(tokenStateAccount.seizedLocked.isZero());
+    });
+  });
+
+  describe("reserve attestation and history", () => {
+    const reserveHistory = pda(Buffer.from("reserve_history"));
+    const buybackAccount = getAssociatedTokenAddressSync(mint, tokenState, true, TOKEN_2022_PROGRAM_ID);
+    const feeAccount = getAssociatedTokenAddressSync(mint, feeTreasury, true, TOKEN_2022_PROGRAM_ID);
+    const reserveAccounts = () => ({
+      tokenState,
+      config,
+      vault,
+      seizureEscrow,
+      buybackAccount,
+      feeTreasury,
+      feeAccount,
+      solPriceFeed: null,
+    });
+    const reserveVaults = () => [{ pubkey: usdtVault, isSigner: false, isWritable: false }];
+    const amountOf = async (account: PublicKey) =>
+      new anchor.BN((await provider.connection.getTokenAccountBalance(account)).value.amount);
+
+    before(async () => {
+      // Program-held DIAMOND accounts; the buyback tests may have created them already
+      for (const owner of [tokenState, feeTreasury]) {
+        await getOrCreateAssociatedTokenAccount(
+          provider.connection,
+          provider.wallet.payer,
+          mint,
+          owner,
+          true,
+          undefined,
+          undefined,
+          TOKEN_2022_PROGRAM_ID
+        );
+      }
+
+      await program.methods
+        .initializeReserveHistory()
+        .accounts({
+          authority: provider.wallet.publicKey,
+          tokenState,
+          reserveHistory,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    });
+
+    it("Verifies the reserve against the registered vaults", async () => {
+      await program.methods
+        .verifyReserve()
+        .accounts(reserveAccounts())
+        .remainingAccounts(reserveVaults())
+        .rpc();
+    });
+
+    it("Refuses a reserve vault that is not registered", async () => {
+      const other = await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        provider.wallet.payer,
+        USDT_MINT,
+        provider.wallet.publicKey
+      );
+      try {
+        await program.methods
+          .verifyReserve()
+          .accounts(reserveAccounts())
+          .remainingAccounts([{ pubkey: other.address, isSigner: false, isWritable: false }])
+          .rpc();
+        assert.fail("valued an unregistered vault");
+      } catch (error) {
+        assert.equal(errorCode(error), "InvalidReserveAccount");
+      }
+    });
+
+    it("Records an attestation, leaving program-held DIAMOND out", async () => {
+      await program.methods
+        .recordReserve()
+        .accounts({ caller: provider.wallet.publicKey, reserveHistory, ...reserveAccounts() })
+        .remainingAccounts(reserveVaults())
+        .rpc();
+
+      const history = await program.account.reserveHistory.fetch(reserveHistory);
+      assert.equal(history.snapshots.length, 1);
+      assert.equal(history.nextIndex, 1);
+
+      const snapshot = history.snapshots[0];
+      const tokenStateAccount = await program.account.tokenState.fetch(tokenState);
+      let circulating = tokenStateAccount.totalSupply.sub(tokenStateAccount.seizedLocked);
+      for (const held of [vault, seizureEscrow, buybackAccount, feeAccount]) {
+        circulating = circulating.sub(await amountOf(held));
+      }
+      assert.ok(snapshot.circulatingSupply.eq(circulating));
+      assert.ok(snapshot.reserveUsd.eq(await amountOf(usdtVault)));
+      assert.ok(snapshot.caller.equals(provider.wallet.publicKey));
+    });
+
+    it("Refuses a second attestation within the interval", async () => {
+      try {
+        await program.methods
+          .recordReserve()
+          .accounts({ caller: provider.wallet.publicKey, reserveHistory, ...reserveAccounts() })
+          .remainingAccounts(reserveVaults())
+          .rpc();
+        assert.fail("recorded twice within the interval");
+      } catch (error) {
+        assert.equal(errorCode(error), "ReserveAttestationTooSoon");
+      }
+    });
+  });
+});
@@ -9,37 +9,6 @@ anchor.setProvider(provider);
 
 const program = anchor.workspace.Diamond as anchor.Program<Diamond>;
 
-// Функция для создания обычного SPL TokenAccount с PDA owner
-async function createTokenAccountWithPDA(connection, payer, mint, owner) {
-  const tokenAccount = Keypair.generate();
-  const lamports = await getMinimumBalanceForRentExemptAccount(connection);
-  const transaction = new anchor.web3.Transaction();
-
-  transaction.add(
-    anchor.web3.SystemProgram.createAccount({
-      fromPubkey: payer.publicKey,
-      newAccountPubkey: tokenAccount.publicKey,
-      space: 165,
-      lamports,
-      programId: TOKEN_PROGRAM_ID,
-    }),
-    createInitializeAccountInstruction(
-      tokenAccount.publicKey,
-      mint,
-      owner,
-      TOKEN_PROGRAM_ID
-    )
-  );
-
-  await anchor.web3.sendAndConfirmTransaction(
-    connection,
-    transaction,
-    [payer, tokenAccount]
-  );
-
-  return tokenAccount.publicKey;
-}
-
 async function main() {
   try {
     // 1. Создаем 5 multisig владельцев (включая наш wallet)
@@ -59,15 +28,11 @@ async function main() {
       console.log(`Owner ${index + 1}:`, owner.toBase58());
     });
 
-    // 2. PDA для token_state, blacklist и vault
+    // 2. PDA для token_state и vault
     const [tokenState] = await PublicKey.findProgramAddress(
       [Buffer.from("token_state_v2")],
       program.programId
     );
-    const [blacklist] = await PublicKey.findProgramAddress(
-      [Buffer.from("blacklist_v2")],
-      program.programId
-    );
     const [vaultPda] = await PublicKey.findProgramAddress(
       [Buffer.from("vault_v2")],
       program.programId
@@ -75,7 +40,6 @@ async function main() {
 
     console.log("\nPDAs:");
     console.log("Token State:", tokenState.toBase58());
-    console.log("Blacklist:", blacklist.toBase58());
     console.log("Vault:", vaultPda.toBase58());
 
     // 3. Создаём mint с authority = wallet
@@ -83,7 +47,7 @@ async function main() {
       provider.connection,
       provider.wallet.payer,
       provider.wallet.publicKey, // authority сначала wallet
-      null,
+      provider.wallet.publicKey, // freeze authority тоже
       9
     );
 
@@ -97,17 +61,18 @@ async function main() {
       'MintTokens',
       tokenState
     );
-
-    console.log("\nMint created:", mint.toBase58());
-
-    // 3.2. Создаём vault вручную (SPL TokenAccount с PDA owner)
-    const vault = await createTokenAccountWithPDA(
+    // Freeze authority нужна PDA для freeze_account/thaw_account
+    await setAuthority(
       provider.connection,
       provider.wallet.payer,
       mint,
-      tokenState // owner = PDA tokenState
+      provider.wallet.publicKey,
+      'FreezeAccount',
+      tokenState
     );
 
+    console.log("\nMint created:", mint.toBase58());
+
     // 4. Создаём multisig аккаунт
     const multisig = Keypair.generate();
     console.log("Multisig account:", multisig.publicKey.toBase58());
@@ -121,9 +86,8 @@ async function main() {
         payer: provider.wallet.publicKey,
         tokenState: tokenState,
         mint: mint,
-        blacklist: blacklist,
         multisig: multisig.publicKey,
-        vault: vault,
+        vault: vaultPda, // created by initialize
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
         rent: SYSVAR_RENT_PUBKEY,